@@ -1,6 +1,10 @@
 mod modules;
 
-use modules::{campaign, countdown, database::Database, dice, entity, fear_tracker, player_character};
+use modules::{
+    adversary_library, attack, campaign, condition, countdown, database::Database, dice,
+    dice_fairness, dice_probability, dice_statistics, encounter, entity, fear_tracker,
+    player_character, roll_macro, spotlight,
+};
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -23,6 +27,9 @@ pub fn run() {
             // Manage database as state
             app.manage(db);
 
+            // Tick running auto-countdowns in the background
+            countdown::spawn_auto_countdown_scheduler(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -56,6 +63,14 @@ pub fn run() {
             countdown::set_tick_label,
             countdown::remove_tick_label,
             countdown::set_all_trackers_visibility,
+            countdown::update_tracker,
+            countdown::start_auto_countdown,
+            countdown::pause_auto_countdown,
+            countdown::resume_auto_countdown,
+            countdown::set_tracker_advancement,
+            countdown::get_tracker_advances,
+            countdown::set_tracker_actions,
+            countdown::get_tracker_chains,
             // Entity commands
             entity::create_entity,
             entity::delete_entity,
@@ -63,17 +78,78 @@ pub fn run() {
             entity::update_entity_hp,
             entity::set_entity_hp,
             entity::apply_damage,
+            entity::repair_entity_armor,
             entity::adjust_entity_stress,
+            entity::get_entity_events,
+            entity::undo_last_entity_event,
             entity::update_entity_thresholds,
             entity::update_entity_name,
+            entity::update_entity_stat_block,
+            entity::update_entity,
             entity::toggle_entity_visibility,
             entity::set_all_entities_visibility,
+            entity::apply_damage_to_entities,
+            entity::update_entities_hp,
+            entity::adjust_entities_stress,
+            entity::duplicate_entities,
+            entity::delete_entities,
+            entity::set_entities_visibility,
+            entity::group_entities,
+            entity::ungroup_entity,
+            // Adversary library commands
+            adversary_library::save_entity_as_template,
+            adversary_library::get_adversary_templates,
+            adversary_library::delete_adversary_template,
+            adversary_library::spawn_adversary_template,
+            // Encounter commands
+            encounter::create_encounter,
+            encounter::get_encounters,
+            encounter::update_encounter,
+            encounter::delete_encounter,
+            encounter::add_entities_to_encounter,
+            encounter::remove_entity_from_encounter,
+            encounter::spawn_template_into_encounter,
+            encounter::activate_encounter,
+            encounter::complete_encounter,
+            // Condition commands
+            condition::add_condition,
+            condition::remove_condition,
+            condition::get_conditions,
+            condition::advance_gm_spotlight,
+            condition::end_scene,
+            // Spotlight commands
+            spotlight::get_spotlight,
+            spotlight::set_spotlight,
+            spotlight::start_spotlight_scene,
+            spotlight::adjust_action_tokens,
+            spotlight::get_spotlight_history,
+            // Attack commands
+            attack::resolve_attack,
+            attack::get_attack_results,
             // Dice commands
-            dice::save_dice_roll,
+            dice::roll_dice,
+            dice::roll_duality,
             dice::get_dice_rolls,
             dice::get_dice_rolls_by_date,
+            dice::set_dice_roll_shared,
+            dice::get_shared_dice_rolls,
             dice::delete_dice_roll,
             dice::clear_dice_history,
+            dice_statistics::get_dice_statistics,
+            dice_probability::calculate_dice_probability,
+            // Dice fairness commands
+            dice_fairness::start_seeded_dice_session,
+            dice_fairness::start_fairness_session,
+            dice_fairness::end_dice_rng_session,
+            dice_fairness::get_dice_rng_sessions,
+            dice_fairness::verify_dice_roll,
+            dice_fairness::verify_dice_rng_session,
+            // Roll macro commands
+            roll_macro::create_roll_macro,
+            roll_macro::get_roll_macros,
+            roll_macro::update_roll_macro,
+            roll_macro::delete_roll_macro,
+            roll_macro::roll_macro,
             // Player character commands
             player_character::create_player_character,
             player_character::get_player_characters,
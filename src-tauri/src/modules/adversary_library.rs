@@ -0,0 +1,303 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, State};
+use uuid::Uuid;
+
+use super::database::Database;
+use super::entity::{
+    emit_entities_update, get_entity_by_id, get_required_campaign_id, insert_entity, row_to_stat_block,
+    stat_block_params, AdversaryRole, AdversaryStatBlock, DamageThresholds, Entity, EntityType,
+    STAT_BLOCK_COLUMNS,
+};
+use super::error::{AppError, AppResult};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Most instances one spawn can create
+pub const MAX_SPAWN_COUNT: u32 = 20;
+
+/// A reusable adversary or NPC, shared by every campaign
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AdversaryTemplate {
+    pub id: String,
+    pub name: String,
+    pub entity_type: EntityType,
+    pub hp_max: i32,
+    pub stress_max: i32,
+    pub thresholds: DamageThresholds,
+    #[serde(flatten)]
+    pub stat_block: AdversaryStatBlock,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// ============================================================================
+// Database Helpers
+// ============================================================================
+
+fn row_to_template(row: &Row) -> rusqlite::Result<AdversaryTemplate> {
+    Ok(AdversaryTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        entity_type: EntityType::from_str(&row.get::<_, String>(2)?),
+        hp_max: row.get(3)?,
+        stress_max: row.get(4)?,
+        thresholds: DamageThresholds {
+            minor: row.get(5)?,
+            major: row.get(6)?,
+            severe: row.get(7)?,
+        },
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        stat_block: row_to_stat_block(row, 10)?,
+    })
+}
+
+const BASE_COLUMNS: &str = "id, name, entity_type, hp_max, stress_max, threshold_minor, threshold_major, threshold_severe, created_at, updated_at";
+
+fn select_columns() -> String {
+    format!("{}, {}", BASE_COLUMNS, STAT_BLOCK_COLUMNS)
+}
+
+pub fn get_template_by_id(conn: &Connection, id: &str) -> AppResult<AdversaryTemplate> {
+    conn.query_row(
+        &format!("SELECT {} FROM adversary_templates WHERE id = ?1", select_columns()),
+        [id],
+        row_to_template,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Adversary template not found: {}", id)))
+}
+
+fn emit_templates_update(app: &tauri::AppHandle) -> AppResult<()> {
+    app.emit("adversary-templates-updated", ())
+        .map_err(|e| AppError::EmitError(e.to_string()))
+}
+
+/// Highest `N` among entities named "`base` N" in a campaign, and whether
+/// one is named exactly `base`
+fn existing_numbering(conn: &Connection, campaign_id: &str, base: &str) -> AppResult<(u32, bool)> {
+    let mut stmt = conn.prepare("SELECT name FROM entities WHERE campaign_id = ?1 AND name LIKE ?2 || '%'")?;
+    let names = stmt
+        .query_map(params![campaign_id, base], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut highest = 0;
+    let mut plain = false;
+    for name in names {
+        match name.strip_prefix(base) {
+            Some("") => plain = true,
+            Some(rest) => {
+                if let Some(n) = rest.strip_prefix(' ').and_then(|n| n.parse::<u32>().ok()) {
+                    highest = highest.max(n);
+                }
+            }
+            None => {}
+        }
+    }
+    Ok((highest, plain))
+}
+
+/// Next free numbered name for another copy of `name`, e.g. "Bandit 6"
+/// when "Bandit 2" to "Bandit 5" exist
+pub fn next_instance_name(conn: &Connection, campaign_id: &str, name: &str) -> AppResult<String> {
+    let base = match name.rsplit_once(' ') {
+        Some((base, n)) if !base.is_empty() && n.parse::<u32>().is_ok() => base,
+        _ => name,
+    };
+    let (highest, plain) = existing_numbering(conn, campaign_id, base)?;
+    let next = if plain { highest.max(1) + 1 } else { highest + 1 };
+    Ok(format!("{} {}", base, next))
+}
+
+/// Create `count` entities from a template in the current campaign, without
+/// emitting. See `spawn_template_into_campaign`.
+pub fn spawn_template(
+    conn: &Connection,
+    template: &AdversaryTemplate,
+    count: u32,
+    visible_to_players: bool,
+) -> AppResult<Vec<Entity>> {
+    let campaign_id = get_required_campaign_id(conn)?;
+    spawn_template_into_campaign(conn, &campaign_id, template, count, visible_to_players)
+}
+
+/// Create `count` entities from a template in a campaign, without emitting.
+/// A single instance keeps the template's name unless it's taken; otherwise
+/// instances are numbered after any already in the campaign and share a new
+/// group.
+pub fn spawn_template_into_campaign(
+    conn: &Connection,
+    campaign_id: &str,
+    template: &AdversaryTemplate,
+    count: u32,
+    visible_to_players: bool,
+) -> AppResult<Vec<Entity>> {
+    if count == 0 || count > MAX_SPAWN_COUNT {
+        return Err(AppError::Validation(format!(
+            "Can spawn between 1 and {} instances at a time",
+            MAX_SPAWN_COUNT
+        )));
+    }
+
+    let (highest, plain) = existing_numbering(conn, campaign_id, &template.name)?;
+    let numbered = count > 1 || plain || highest > 0;
+    let first = if plain { highest.max(1) + 1 } else { highest + 1 };
+    // Instances spawned together form a group, so Minion spillover reaches them
+    let group_id = (count > 1).then(|| Uuid::new_v4().to_string());
+
+    let mut spawned = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let entity = Entity {
+            id: Uuid::new_v4().to_string(),
+            campaign_id: campaign_id.to_string(),
+            name: if numbered {
+                format!("{} {}", template.name, first + i)
+            } else {
+                template.name.clone()
+            },
+            hp_current: template.hp_max,
+            hp_max: template.hp_max,
+            stress_current: 0,
+            stress_max: template.stress_max,
+            thresholds: template.thresholds.clone(),
+            visible_to_players,
+            entity_type: template.entity_type.clone(),
+            group_id: group_id.clone(),
+            armor_marked: 0,
+            stat_block: template.stat_block.clone(),
+            conditions: Vec::new(),
+        };
+        insert_entity(conn, &entity)?;
+        spawned.push(entity);
+    }
+
+    Ok(spawned)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Save an entity's stat block, HP, Stress and thresholds to the library.
+/// `name` defaults to the entity's name.
+#[tauri::command]
+pub fn save_entity_as_template(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    entity_id: String,
+    name: Option<String>,
+) -> AppResult<AdversaryTemplate> {
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &entity_id)?;
+        let name = name.unwrap_or(entity.name);
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Template name cannot be empty".to_string()));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(id.clone()),
+            Box::new(name.trim().to_string()),
+            Box::new(entity.entity_type.as_str()),
+            Box::new(entity.hp_max),
+            Box::new(entity.stress_max),
+            Box::new(entity.thresholds.minor),
+            Box::new(entity.thresholds.major),
+            Box::new(entity.thresholds.severe),
+        ];
+        values.extend(stat_block_params(&entity.stat_block)?);
+
+        conn.execute(
+            &format!(
+                "INSERT INTO adversary_templates (id, name, entity_type, hp_max, stress_max, threshold_minor, threshold_major, threshold_severe, {})
+                 VALUES ({})",
+                STAT_BLOCK_COLUMNS,
+                (1..=values.len()).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ")
+            ),
+            rusqlite::params_from_iter(values.iter()),
+        )?;
+
+        let template = get_template_by_id(conn, &id)?;
+        emit_templates_update(&app)?;
+        Ok(template)
+    })
+}
+
+/// Browse the library. `search` matches names, motives and feature text;
+/// the other filters narrow by type, tier and role.
+#[tauri::command]
+pub fn get_adversary_templates(
+    db: State<Database>,
+    search: Option<String>,
+    entity_type: Option<EntityType>,
+    tier: Option<i32>,
+    role: Option<AdversaryRole>,
+) -> AppResult<Vec<AdversaryTemplate>> {
+    let search = search.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM adversary_templates
+             WHERE (?1 IS NULL OR name LIKE '%' || ?1 || '%' OR motives_and_tactics LIKE '%' || ?1 || '%' OR features LIKE '%' || ?1 || '%')
+               AND (?2 IS NULL OR entity_type = ?2)
+               AND (?3 IS NULL OR tier = ?3)
+               AND (?4 IS NULL OR role = ?4)
+             ORDER BY name COLLATE NOCASE, created_at",
+            select_columns()
+        ))?;
+
+        let templates = stmt
+            .query_map(
+                params![
+                    search,
+                    entity_type.as_ref().map(|t| t.as_str()),
+                    tier,
+                    role.map(|r| r.as_str())
+                ],
+                row_to_template,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(templates)
+    })
+}
+
+#[tauri::command]
+pub fn delete_adversary_template(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let rows_affected = conn.execute("DELETE FROM adversary_templates WHERE id = ?1", [&id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::EntityNotFound(format!("Adversary template not found: {}", id)));
+        }
+
+        emit_templates_update(&app)?;
+        Ok(())
+    })
+}
+
+/// Add `count` instances of a template to the current campaign in one go,
+/// named e.g. "Bandit 1" to "Bandit 4"
+#[tauri::command]
+pub fn spawn_adversary_template(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    template_id: String,
+    count: Option<u32>,
+    visible_to_players: Option<bool>,
+) -> AppResult<Vec<Entity>> {
+    db.with_conn(|conn| {
+        let template = get_template_by_id(conn, &template_id)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let spawned = spawn_template(&tx, &template, count.unwrap_or(1), visible_to_players.unwrap_or(false))?;
+        tx.commit()?;
+
+        if let Some(entity) = spawned.first() {
+            emit_entities_update(&app, conn, &entity.campaign_id)?;
+        }
+        Ok(spawned)
+    })
+}
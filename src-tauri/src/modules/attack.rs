@@ -0,0 +1,365 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use super::database::Database;
+use super::dice::{emit_duality_consequences, emit_roll_saved, record_duality_roll, record_roll, DiceRoll, RollSettings};
+use super::dice_engine::{self, DieKind, DualityOutcome, RollOptions, RollResult, RollVariables};
+use super::entity::{
+    apply_damage_to_entity, emit_entities_update, get_entity_by_id, DamageInput, DamageResult, DamageType,
+    EventSource,
+};
+use super::error::{AppError, AppResult};
+use super::player_character::{get_player_character_by_id, PlayerCharacter};
+use super::roll_macro::character_variables;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+const EVENT_NAME: &str = "attack-resolved";
+
+/// Die adversaries and other entities roll to attack
+const ENTITY_ATTACK_DIE: &str = "1d20";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackerType {
+    PlayerCharacter,
+    Entity,
+}
+
+impl AttackerType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AttackerType::PlayerCharacter => "player_character",
+            AttackerType::Entity => "entity",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "player_character" => AttackerType::PlayerCharacter,
+            _ => AttackerType::Entity,
+        }
+    }
+}
+
+/// An attack to resolve. Player characters roll Duality Dice plus
+/// `attack_modifier`; entities roll a d20 plus `attack_modifier`, defaulting
+/// to their stat block's attack modifier and standard attack damage (a
+/// weakened Horde's reduced damage). For
+/// player characters both `attack_modifier` and `damage` may use roll
+/// variables such as `@agility` or `@{proficiency}d8`.
+#[derive(Debug, Deserialize)]
+pub struct AttackRequest {
+    pub attacker_type: AttackerType,
+    pub attacker_id: String,
+    pub target_entity_id: String,
+    #[serde(default)]
+    pub attack_modifier: Option<String>,
+    #[serde(default)]
+    pub damage: Option<String>,
+    /// Defaults to the entity attacker's standard attack type, else physical
+    #[serde(default)]
+    pub damage_type: Option<DamageType>,
+    /// Direct damage can't be reduced with Armor Slots
+    #[serde(default)]
+    pub direct: bool,
+    /// Overrides the target's Difficulty
+    #[serde(default)]
+    pub difficulty: Option<i32>,
+    /// Advantage and bonus dice on the attack roll
+    #[serde(default)]
+    pub options: RollOptions,
+    #[serde(default)]
+    pub shared_with_players: bool,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// A resolved attack as stored, linking its rolls to the damage it did
+#[derive(Clone, Serialize, Debug)]
+pub struct AttackResult {
+    pub id: String,
+    pub campaign_id: String,
+    pub attacker_type: AttackerType,
+    pub attacker_id: String,
+    pub target_entity_id: String,
+    pub attack_roll_id: Option<String>,
+    /// `None` on a miss
+    pub damage_roll_id: Option<String>,
+    pub difficulty: i32,
+    pub hit: bool,
+    pub critical: bool,
+    /// Damage dealt, including the maximum dice added on a critical
+    pub damage_total: i32,
+    pub hp_marked: i32,
+    pub threshold_hit: Option<String>,
+    pub created_at: String,
+}
+
+/// Everything an attack changed, returned and emitted as `attack-resolved`
+#[derive(Clone, Serialize)]
+pub struct ResolvedAttack {
+    pub attack: AttackResult,
+    pub attack_roll: DiceRoll,
+    pub damage_roll: Option<DiceRoll>,
+    pub damage: Option<DamageResult>,
+    /// Duality dice, for player character attacks
+    pub hope: Option<i32>,
+    pub fear: Option<i32>,
+    pub fear_level: Option<i32>,
+    pub character: Option<PlayerCharacter>,
+}
+
+// ============================================================================
+// Database Helpers
+// ============================================================================
+
+fn row_to_attack_result(row: &Row) -> rusqlite::Result<AttackResult> {
+    Ok(AttackResult {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        attacker_type: AttackerType::from_str(&row.get::<_, String>(2)?),
+        attacker_id: row.get(3)?,
+        target_entity_id: row.get(4)?,
+        attack_roll_id: row.get(5)?,
+        damage_roll_id: row.get(6)?,
+        difficulty: row.get(7)?,
+        hit: row.get::<_, i32>(8)? != 0,
+        critical: row.get::<_, i32>(9)? != 0,
+        damage_total: row.get(10)?,
+        hp_marked: row.get(11)?,
+        threshold_hit: row.get(12)?,
+        created_at: row.get(13)?,
+    })
+}
+
+const SELECT_COLUMNS: &str = "id, campaign_id, attacker_type, attacker_id, target_entity_id, attack_roll_id, damage_roll_id, difficulty, hit, critical, damage_total, hp_marked, threshold_hit, created_at";
+
+fn get_attack_result_by_id(conn: &Connection, id: &str) -> AppResult<AttackResult> {
+    conn.query_row(
+        &format!("SELECT {} FROM attack_results WHERE id = ?1", SELECT_COLUMNS),
+        [id],
+        row_to_attack_result,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Attack not found: {}", id)))
+}
+
+/// Evaluate a modifier expression such as `@agility+1`; empty means 0
+fn resolve_modifier(modifier: Option<&str>, variables: &RollVariables) -> AppResult<i32> {
+    match modifier.map(str::trim) {
+        None | Some("") => Ok(0),
+        Some(modifier) => dice_engine::evaluate_constant(&dice_engine::substitute_variables(modifier, variables)?),
+    }
+}
+
+/// Extra damage on a critical: the highest face of every damage die that counted
+fn critical_bonus(result: &RollResult) -> i32 {
+    result
+        .dice
+        .iter()
+        .filter(|d| d.kind == DieKind::Standard && !d.dropped && !d.exploded)
+        .map(|d| d.sides)
+        .sum()
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Roll an attack from a player character or entity against a target entity.
+/// On a hit the damage is rolled and marked on the target through its
+/// thresholds, as with `apply_damage`. The rolls, the damage and the stored
+/// `AttackResult` are written in one transaction. After commit each roll is
+/// emitted like any other roll, followed by `attack-resolved`.
+#[tauri::command]
+pub fn resolve_attack(db: State<Database>, app: AppHandle, attack: AttackRequest) -> AppResult<ResolvedAttack> {
+    db.with_conn(|conn| {
+        let target = get_entity_by_id(conn, &attack.target_entity_id)?;
+        let campaign_id = target.campaign_id.clone();
+
+        let difficulty = attack.difficulty.or(target.stat_block.difficulty).ok_or_else(|| {
+            AppError::Validation(format!("{} has no Difficulty; pass one to roll against", target.name))
+        })?;
+
+        let (attacker_character, variables, modifier, damage, damage_type) = match attack.attacker_type {
+            AttackerType::PlayerCharacter => {
+                let character = get_player_character_by_id(conn, &attack.attacker_id)?;
+                if character.campaign_id != campaign_id {
+                    return Err(AppError::Validation(
+                        "Attacker belongs to a different campaign".to_string(),
+                    ));
+                }
+                let variables = character_variables(&character);
+                let modifier = resolve_modifier(attack.attack_modifier.as_deref(), &variables)?;
+                let damage = attack.damage.clone().ok_or_else(|| {
+                    AppError::Validation("Damage notation is required for player character attacks".to_string())
+                })?;
+                (Some(character), variables, modifier, damage, attack.damage_type.unwrap_or_default())
+            }
+            AttackerType::Entity => {
+                if attack.attacker_id == target.id {
+                    return Err(AppError::InvalidOperation("An entity can't attack itself".to_string()));
+                }
+                let attacker = get_entity_by_id(conn, &attack.attacker_id)?;
+                if attacker.campaign_id != campaign_id {
+                    return Err(AppError::Validation(
+                        "Attacker belongs to a different campaign".to_string(),
+                    ));
+                }
+                let variables = RollVariables::default();
+                let modifier = match attack.attack_modifier.as_deref() {
+                    Some(modifier) => resolve_modifier(Some(modifier), &variables)?,
+                    None => attacker.stat_block.attack_modifier,
+                };
+                let damage = match (&attack.damage, attacker.standard_attack_damage()) {
+                    (Some(damage), _) => damage.clone(),
+                    (None, Some(standard)) => standard.to_string(),
+                    (None, None) => {
+                        return Err(AppError::Validation(format!(
+                            "{} has no standard attack; pass damage notation",
+                            attacker.name
+                        )));
+                    }
+                };
+                let damage_type = attack
+                    .damage_type
+                    .or(attacker.stat_block.attack.as_ref().map(|a| a.damage_type))
+                    .unwrap_or_default();
+                (None, variables, modifier, damage, damage_type)
+            }
+        };
+
+        let damage_notation = dice_engine::substitute_variables(&damage, &variables)?;
+        let settings = RollSettings {
+            campaign_id: &campaign_id,
+            player_character_id: attacker_character.as_ref().map(|c| c.id.as_str()),
+            options: attack.options.clone(),
+            shared_with_players: attack.shared_with_players,
+            secret: attack.secret,
+        };
+
+        let tx = conn.unchecked_transaction()?;
+
+        let (attack_roll, duality, hit, critical) = match attack.attacker_type {
+            AttackerType::PlayerCharacter => {
+                let result = record_duality_roll(&tx, modifier, Some(difficulty), &settings)?;
+                let outcome = result.roll.outcome;
+                let hit = outcome.and_then(|o| o.is_success()).unwrap_or(false);
+                let critical = outcome == Some(DualityOutcome::CriticalSuccess);
+                (result.roll.clone(), Some(result), hit, critical)
+            }
+            AttackerType::Entity => {
+                let notation = match modifier {
+                    0 => ENTITY_ATTACK_DIE.to_string(),
+                    m if m > 0 => format!("{}+{}", ENTITY_ATTACK_DIE, m),
+                    m => format!("{}{}", ENTITY_ATTACK_DIE, m),
+                };
+                let (roll, _) = record_roll(&tx, &notation, &settings)?;
+                // A natural 20 always hits
+                let hit = roll.is_crit || roll.total >= difficulty;
+                let critical = roll.is_crit;
+                (roll, None, hit, critical)
+            }
+        };
+
+        let (damage_roll, damage) = if hit {
+            let damage_settings = RollSettings {
+                options: RollOptions::default(),
+                ..settings
+            };
+            let (roll, result) = record_roll(&tx, &damage_notation, &damage_settings)?;
+            let bonus = if critical { critical_bonus(&result) } else { 0 };
+            let total = roll.total.saturating_add(bonus).max(0);
+            let input = DamageInput {
+                amount: total,
+                damage_type,
+                direct: attack.direct,
+            };
+            let damage = apply_damage_to_entity(&tx, target, input, EventSource::Attack)?;
+            (Some(roll), Some((total, damage)))
+        } else {
+            (None, None)
+        };
+
+        let id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO attack_results (id, campaign_id, attacker_type, attacker_id, target_entity_id, attack_roll_id, damage_roll_id, difficulty, hit, critical, damage_total, hp_marked, threshold_hit)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                id,
+                campaign_id,
+                attack.attacker_type.as_str(),
+                attack.attacker_id,
+                attack.target_entity_id,
+                attack_roll.id,
+                damage_roll.as_ref().map(|r| r.id.as_str()),
+                difficulty,
+                hit as i32,
+                critical as i32,
+                damage.as_ref().map(|(total, _)| *total).unwrap_or(0),
+                damage.as_ref().map(|(_, d)| d.damage_dealt).unwrap_or(0),
+                damage.as_ref().and_then(|(_, d)| d.threshold_hit.clone()),
+            ],
+        )?;
+        let stored = get_attack_result_by_id(&tx, &id)?;
+
+        tx.commit()?;
+
+        let resolved = ResolvedAttack {
+            attack: stored,
+            attack_roll,
+            damage_roll,
+            damage: damage.map(|(_, d)| d),
+            hope: duality.as_ref().map(|d| d.hope),
+            fear: duality.as_ref().map(|d| d.fear),
+            fear_level: duality.as_ref().and_then(|d| d.fear_level),
+            character: duality.as_ref().and_then(|d| d.character.clone()),
+        };
+
+        emit_roll_saved(&app, &resolved.attack_roll);
+        if let Some(roll) = &resolved.damage_roll {
+            emit_roll_saved(&app, roll);
+        }
+        if resolved.damage.is_some() {
+            emit_entities_update(&app, conn, &campaign_id)?;
+        }
+        if let Some(duality) = &duality {
+            emit_duality_consequences(&app, conn, duality)?;
+        }
+        app.emit(EVENT_NAME, &resolved)
+            .map_err(|e| AppError::EmitError(e.to_string()))?;
+
+        Ok(resolved)
+    })
+}
+
+/// Past attacks in a campaign, newest first, optionally against one target
+#[tauri::command]
+pub fn get_attack_results(
+    db: State<Database>,
+    campaign_id: String,
+    target_entity_id: Option<String>,
+    limit: Option<i32>,
+) -> AppResult<Vec<AttackResult>> {
+    let limit = limit.unwrap_or(50);
+
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM attack_results
+             WHERE campaign_id = ?1 AND (?2 IS NULL OR target_entity_id = ?2)
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?3",
+            SELECT_COLUMNS
+        ))?;
+
+        let results = stmt
+            .query_map(params![campaign_id, target_entity_id, limit], row_to_attack_result)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    })
+}
@@ -0,0 +1,493 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+use super::countdown::get_tracker_by_id;
+use super::database::Database;
+use super::entity::{
+    apply_entity_hp_change, apply_entity_stress, emit_entities_update, get_entity_by_id, get_required_campaign_id,
+    EventSource,
+};
+use super::error::{AppError, AppResult};
+use super::player_character::{
+    apply_hp_adjustment, apply_stress_adjustment, emit_player_character_update, get_player_character_by_id,
+};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConditionKind {
+    Vulnerable,
+    Restrained,
+    Hidden,
+    Custom,
+}
+
+impl ConditionKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ConditionKind::Vulnerable => "vulnerable",
+            ConditionKind::Restrained => "restrained",
+            ConditionKind::Hidden => "hidden",
+            ConditionKind::Custom => "custom",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "vulnerable" => ConditionKind::Vulnerable,
+            "restrained" => ConditionKind::Restrained,
+            "hidden" => ConditionKind::Hidden,
+            _ => ConditionKind::Custom,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            ConditionKind::Vulnerable => "Vulnerable",
+            ConditionKind::Restrained => "Restrained",
+            ConditionKind::Hidden => "Hidden",
+            ConditionKind::Custom => "Condition",
+        }
+    }
+}
+
+/// What a condition is attached to
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionTarget {
+    Entity,
+    PlayerCharacter,
+}
+
+/// How long a condition lasts before it's cleared automatically
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionDuration {
+    /// Until removed by hand
+    #[default]
+    Indefinite,
+    EndOfScene,
+    /// A number of GM spotlights
+    Spotlights { remaining: i32 },
+    /// Until the countdown reaches 0
+    Countdown { tracker_id: String },
+}
+
+impl ConditionDuration {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ConditionDuration::Indefinite => "indefinite",
+            ConditionDuration::EndOfScene => "end_of_scene",
+            ConditionDuration::Spotlights { .. } => "spotlights",
+            ConditionDuration::Countdown { .. } => "countdown",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Condition {
+    pub id: String,
+    pub campaign_id: String,
+    pub target_type: ConditionTarget,
+    pub target_id: String,
+    pub kind: ConditionKind,
+    pub name: String,
+    pub description: Option<String>,
+    pub visible_to_players: bool,
+    pub duration: ConditionDuration,
+    /// HP marked at the end of each GM spotlight, e.g. bleeding
+    pub tick_damage: i32,
+    /// Stress marked at the end of each GM spotlight
+    pub tick_stress: i32,
+    pub created_at: String,
+}
+
+/// A condition to attach. `name` defaults to the kind, and is required for
+/// custom conditions.
+#[derive(Debug, Deserialize)]
+pub struct NewCondition {
+    pub kind: ConditionKind,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub visible_to_players: bool,
+    #[serde(default)]
+    pub duration: ConditionDuration,
+    #[serde(default)]
+    pub tick_damage: i32,
+    #[serde(default)]
+    pub tick_stress: i32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One condition's effects at the end of a GM spotlight
+#[derive(Clone, Serialize, Debug)]
+pub struct ConditionTick {
+    pub condition: Condition,
+    pub hp_marked: i32,
+    pub stress_applied: i32,
+    pub hp_overflow_damage: i32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SpotlightResult {
+    /// Conditions with per-tick effects, after applying them
+    pub ticks: Vec<ConditionTick>,
+    /// Conditions whose spotlight duration ran out
+    pub expired: Vec<Condition>,
+}
+
+// ============================================================================
+// Database Helpers
+// ============================================================================
+
+const SELECT_COLUMNS: &str = "id, campaign_id, entity_id, player_character_id, kind, name, description, visible_to_players, duration_type, spotlights_remaining, countdown_id, tick_damage, tick_stress, created_at";
+
+fn row_to_condition(row: &Row) -> rusqlite::Result<Condition> {
+    let entity_id: Option<String> = row.get(2)?;
+    let player_character_id: Option<String> = row.get(3)?;
+    let (target_type, target_id) = match (entity_id, player_character_id) {
+        (Some(id), _) => (ConditionTarget::Entity, id),
+        (None, id) => (ConditionTarget::PlayerCharacter, id.unwrap_or_default()),
+    };
+
+    let duration = match row.get::<_, String>(8)?.as_str() {
+        "end_of_scene" => ConditionDuration::EndOfScene,
+        "spotlights" => ConditionDuration::Spotlights {
+            remaining: row.get::<_, Option<i32>>(9)?.unwrap_or(0),
+        },
+        "countdown" => match row.get::<_, Option<String>>(10)? {
+            Some(tracker_id) => ConditionDuration::Countdown { tracker_id },
+            None => ConditionDuration::Indefinite,
+        },
+        _ => ConditionDuration::Indefinite,
+    };
+
+    Ok(Condition {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        target_type,
+        target_id,
+        kind: ConditionKind::from_str(&row.get::<_, String>(4)?),
+        name: row.get(5)?,
+        description: row.get(6)?,
+        visible_to_players: row.get(7)?,
+        duration,
+        tick_damage: row.get(11)?,
+        tick_stress: row.get(12)?,
+        created_at: row.get(13)?,
+    })
+}
+
+fn target_column(target: ConditionTarget) -> &'static str {
+    match target {
+        ConditionTarget::Entity => "entity_id",
+        ConditionTarget::PlayerCharacter => "player_character_id",
+    }
+}
+
+fn get_condition_by_id(conn: &Connection, id: &str) -> AppResult<Condition> {
+    conn.query_row(
+        &format!("SELECT {} FROM conditions WHERE id = ?1", SELECT_COLUMNS),
+        [id],
+        row_to_condition,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Condition not found: {}", id)))
+}
+
+fn query_conditions(conn: &Connection, filter: &str, value: &str) -> AppResult<Vec<Condition>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM conditions WHERE {} ORDER BY created_at, rowid",
+        SELECT_COLUMNS, filter
+    ))?;
+
+    let conditions = stmt
+        .query_map([value], row_to_condition)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(conditions)
+}
+
+pub fn get_conditions_for_target(conn: &Connection, target: ConditionTarget, target_id: &str) -> AppResult<Vec<Condition>> {
+    query_conditions(conn, &format!("{} = ?1", target_column(target)), target_id)
+}
+
+/// Every condition in a campaign on one kind of target, keyed by target id
+pub fn get_conditions_by_target(
+    conn: &Connection,
+    campaign_id: &str,
+    target: ConditionTarget,
+) -> AppResult<HashMap<String, Vec<Condition>>> {
+    let conditions = query_conditions(
+        conn,
+        &format!("campaign_id = ?1 AND {} IS NOT NULL", target_column(target)),
+        campaign_id,
+    )?;
+
+    let mut by_target: HashMap<String, Vec<Condition>> = HashMap::new();
+    for condition in conditions {
+        by_target.entry(condition.target_id.clone()).or_default().push(condition);
+    }
+    Ok(by_target)
+}
+
+fn delete_conditions(conn: &Connection, conditions: &[Condition]) -> AppResult<()> {
+    for condition in conditions {
+        conn.execute("DELETE FROM conditions WHERE id = ?1", [&condition.id])?;
+    }
+    Ok(())
+}
+
+/// Clear every condition lasting until a countdown completes, without emitting
+pub fn expire_countdown_conditions(conn: &Connection, tracker_id: &str) -> AppResult<Vec<Condition>> {
+    let expired = query_conditions(conn, "duration_type = 'countdown' AND countdown_id = ?1", tracker_id)?;
+    delete_conditions(conn, &expired)?;
+    Ok(expired)
+}
+
+/// Clear every condition lasting until the end of the scene, without emitting
+pub fn expire_scene_conditions(conn: &Connection, campaign_id: &str) -> AppResult<Vec<Condition>> {
+    let expired = query_conditions(conn, "campaign_id = ?1 AND duration_type = 'end_of_scene'", campaign_id)?;
+    delete_conditions(conn, &expired)?;
+    Ok(expired)
+}
+
+/// Apply each condition's per-tick effects, then count down spotlight
+/// durations, clearing those that run out. Doesn't emit.
+pub fn tick_conditions(conn: &Connection, campaign_id: &str) -> AppResult<SpotlightResult> {
+    let conditions = query_conditions(conn, "campaign_id = ?1", campaign_id)?;
+    let mut ticks = Vec::new();
+    let mut expired = Vec::new();
+
+    for condition in conditions {
+        if condition.tick_damage > 0 || condition.tick_stress > 0 {
+            ticks.push(apply_tick(conn, &condition)?);
+        }
+
+        if let ConditionDuration::Spotlights { remaining } = condition.duration {
+            if remaining <= 1 {
+                expired.push(condition);
+            } else {
+                conn.execute(
+                    "UPDATE conditions SET spotlights_remaining = ?1 WHERE id = ?2",
+                    params![remaining - 1, condition.id],
+                )?;
+            }
+        }
+    }
+
+    delete_conditions(conn, &expired)?;
+    Ok(SpotlightResult { ticks, expired })
+}
+
+fn apply_tick(conn: &Connection, condition: &Condition) -> AppResult<ConditionTick> {
+    let (hp_marked, stress_applied, hp_overflow_damage) = match condition.target_type {
+        ConditionTarget::Entity => {
+            let entity = get_entity_by_id(conn, &condition.target_id)?;
+            let hp_before = entity.hp_current;
+            let entity = apply_entity_hp_change(conn, entity, -condition.tick_damage, EventSource::Condition)?;
+            let hp_marked = hp_before - entity.hp_current;
+            let stress = apply_entity_stress(conn, entity, condition.tick_stress, EventSource::Condition)?;
+            (hp_marked, stress.stress_applied, stress.hp_overflow_damage)
+        }
+        ConditionTarget::PlayerCharacter => {
+            let hp_before = get_player_character_by_id(conn, &condition.target_id)?.hp_current;
+            let character = apply_hp_adjustment(conn, &condition.target_id, -condition.tick_damage)?;
+            let hp_marked = hp_before - character.hp_current;
+            let stress = apply_stress_adjustment(conn, &condition.target_id, condition.tick_stress)?;
+            (hp_marked, stress.stress_applied, stress.hp_overflow_damage)
+        }
+    };
+
+    Ok(ConditionTick {
+        condition: condition.clone(),
+        hp_marked,
+        stress_applied,
+        hp_overflow_damage,
+    })
+}
+
+/// Emit one `entities-updated` if any entity was affected, and an update
+/// for each affected player character
+pub fn emit_condition_changes<'a>(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    campaign_id: &str,
+    conditions: impl IntoIterator<Item = &'a Condition>,
+) -> AppResult<()> {
+    let mut entities_changed = false;
+    let mut characters: Vec<&str> = Vec::new();
+    for condition in conditions {
+        match condition.target_type {
+            ConditionTarget::Entity => entities_changed = true,
+            ConditionTarget::PlayerCharacter => {
+                if !characters.contains(&condition.target_id.as_str()) {
+                    characters.push(&condition.target_id);
+                }
+            }
+        }
+    }
+
+    if entities_changed {
+        emit_entities_update(app, conn, campaign_id)?;
+    }
+    for id in characters {
+        let character = get_player_character_by_id(conn, id)?;
+        emit_player_character_update(app, &character);
+    }
+    Ok(())
+}
+
+fn validate_condition(conn: &Connection, campaign_id: &str, condition: &NewCondition) -> AppResult<()> {
+    if condition.kind == ConditionKind::Custom
+        && condition.name.as_deref().map(str::trim).unwrap_or_default().is_empty()
+    {
+        return Err(AppError::Validation("Custom conditions need a name".to_string()));
+    }
+
+    if condition.tick_damage < 0 || condition.tick_stress < 0 {
+        return Err(AppError::Validation("Per-spotlight damage and Stress cannot be negative".to_string()));
+    }
+
+    match &condition.duration {
+        ConditionDuration::Spotlights { remaining } if *remaining < 1 => {
+            return Err(AppError::Validation("A condition must last at least one spotlight".to_string()));
+        }
+        ConditionDuration::Countdown { tracker_id } => {
+            let tracker = get_tracker_by_id(conn, tracker_id)?;
+            if tracker.campaign_id != campaign_id {
+                return Err(AppError::Validation("Countdown belongs to a different campaign".to_string()));
+            }
+            if tracker.current == 0 {
+                return Err(AppError::InvalidOperation(format!("{} has already completed", tracker.name)));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn add_condition(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    target_type: ConditionTarget,
+    target_id: String,
+    condition: NewCondition,
+) -> AppResult<Condition> {
+    db.with_conn(|conn| {
+        let campaign_id = match target_type {
+            ConditionTarget::Entity => get_entity_by_id(conn, &target_id)?.campaign_id,
+            ConditionTarget::PlayerCharacter => get_player_character_by_id(conn, &target_id)?.campaign_id,
+        };
+        validate_condition(conn, &campaign_id, &condition)?;
+
+        let id = Uuid::new_v4().to_string();
+        let name = condition
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(condition.kind.label())
+            .to_string();
+        let (spotlights_remaining, countdown_id) = match &condition.duration {
+            ConditionDuration::Spotlights { remaining } => (Some(*remaining), None),
+            ConditionDuration::Countdown { tracker_id } => (None, Some(tracker_id.clone())),
+            _ => (None, None),
+        };
+
+        conn.execute(
+            &format!(
+                "INSERT INTO conditions (id, campaign_id, {}, kind, name, description, visible_to_players, duration_type, spotlights_remaining, countdown_id, tick_damage, tick_stress)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                target_column(target_type)
+            ),
+            params![
+                id,
+                campaign_id,
+                target_id,
+                condition.kind.as_str(),
+                name,
+                condition.description,
+                condition.visible_to_players,
+                condition.duration.as_str(),
+                spotlights_remaining,
+                countdown_id,
+                condition.tick_damage,
+                condition.tick_stress,
+            ],
+        )?;
+
+        let created = get_condition_by_id(conn, &id)?;
+        emit_condition_changes(&app, conn, &campaign_id, [&created])?;
+        Ok(created)
+    })
+}
+
+#[tauri::command]
+pub fn remove_condition(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let condition = get_condition_by_id(conn, &id)?;
+        conn.execute("DELETE FROM conditions WHERE id = ?1", [&id])?;
+
+        emit_condition_changes(&app, conn, &condition.campaign_id, [&condition])?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_conditions(
+    db: State<Database>,
+    target_type: ConditionTarget,
+    target_id: String,
+) -> AppResult<Vec<Condition>> {
+    db.with_conn(|conn| get_conditions_for_target(conn, target_type, &target_id))
+}
+
+/// End a GM spotlight in the current campaign: apply per-tick effects such
+/// as bleeding, and clear conditions whose spotlight count runs out
+#[tauri::command]
+pub fn advance_gm_spotlight(db: State<Database>, app: tauri::AppHandle) -> AppResult<SpotlightResult> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let result = tick_conditions(&tx, &campaign_id)?;
+        tx.commit()?;
+
+        let changed = result.ticks.iter().map(|t| &t.condition).chain(&result.expired);
+        emit_condition_changes(&app, conn, &campaign_id, changed)?;
+        Ok(result)
+    })
+}
+
+/// Clear every condition lasting until the end of the scene, returning them
+#[tauri::command]
+pub fn end_scene(db: State<Database>, app: tauri::AppHandle) -> AppResult<Vec<Condition>> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let expired = expire_scene_conditions(&tx, &campaign_id)?;
+        tx.commit()?;
+
+        emit_condition_changes(&app, conn, &campaign_id, &expired)?;
+        Ok(expired)
+    })
+}
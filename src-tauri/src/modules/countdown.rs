@@ -1,12 +1,18 @@
 use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::{Emitter, State};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager, State};
 use uuid::Uuid;
 
 use super::campaign::get_current_campaign_id;
+use super::adversary_library::{get_template_by_id, spawn_template_into_campaign, MAX_SPAWN_COUNT};
+use super::condition::{emit_condition_changes, expire_countdown_conditions, Condition};
 use super::database::Database;
+use super::dice_engine::DualityOutcome;
+use super::entity::emit_entities_update;
 use super::error::{AppError, AppResult};
+use super::fear_tracker::{apply_fear_adjustment, emit_fear_update};
 
 // ============================================================================
 // Types
@@ -24,11 +30,28 @@ pub struct CountdownTracker {
     pub hide_name_from_players: bool,
     pub tracker_type: TrackerType,
     #[serde(default)]
+    pub mode: TrackerMode,
+    #[serde(default)]
+    pub cycle: i32,  // Completed loops of a looping countdown
+    #[serde(default)]
     pub auto_interval: i32,  // Auto-countdown interval in seconds (0 = disabled)
     #[serde(default = "default_true")]
     pub notify_on_complete: bool,  // Show notification when auto-countdown hits 0
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tick_labels: Option<HashMap<i32, String>>,
+    #[serde(default)]
+    pub auto_state: AutoCountdownState,
+    pub started_at: Option<i64>,  // Unix ms when the auto-countdown was started
+    pub next_tick_at: Option<i64>,  // Unix ms of the next scheduled tick while running
+    pub paused_remaining_ms: Option<i64>,  // Time left until the next tick while paused
+    #[serde(default)]
+    pub linked_to_rolls: bool,  // Advanced automatically by duality roll outcomes
+    #[serde(default)]
+    pub advancement: HashMap<DualityOutcome, i32>,  // Ticks to advance per roll outcome
+    #[serde(default)]
+    pub revealed_ticks: Vec<i32>,  // Tick labels revealed to players
+    #[serde(default)]
+    pub completion_actions: Vec<CompletionAction>,  // Run in order when the tracker completes
 }
 
 fn default_true() -> bool {
@@ -58,16 +81,234 @@ impl TrackerType {
     }
 }
 
+/// How a tracker moves and when it completes
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackerMode {
+    /// Counts down from `max` and completes at 0
+    #[default]
+    Countdown,
+    /// Counts up from 0 without a cap and completes when it first reaches `max`
+    CountUp,
+    /// A clock of `max` segments that completes when every segment is filled
+    Progress,
+    /// Counts down from `max`; at 0 it resets to `max` and starts another cycle
+    Looping,
+}
+
+impl TrackerMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TrackerMode::Countdown => "countdown",
+            TrackerMode::CountUp => "count_up",
+            TrackerMode::Progress => "progress",
+            TrackerMode::Looping => "looping",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "count_up" => TrackerMode::CountUp,
+            "progress" => TrackerMode::Progress,
+            "looping" => TrackerMode::Looping,
+            _ => TrackerMode::Countdown,
+        }
+    }
+
+    /// Value a new tracker starts at
+    pub fn start_value(&self, max: i32) -> i32 {
+        match self {
+            TrackerMode::Countdown | TrackerMode::Looping => max,
+            TrackerMode::CountUp | TrackerMode::Progress => 0,
+        }
+    }
+
+    /// Sign of one tick of advancement toward completion
+    fn direction(&self) -> i32 {
+        match self {
+            TrackerMode::Countdown | TrackerMode::Looping => -1,
+            TrackerMode::CountUp | TrackerMode::Progress => 1,
+        }
+    }
+
+    fn completion(&self) -> TrackerCompletion {
+        match self {
+            TrackerMode::Countdown => TrackerCompletion::Expired,
+            TrackerMode::CountUp => TrackerCompletion::TargetReached,
+            TrackerMode::Progress => TrackerCompletion::Filled,
+            TrackerMode::Looping => TrackerCompletion::Looped,
+        }
+    }
+}
+
+/// How a tracker completed, reported in `countdown-completed`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackerCompletion {
+    /// A countdown ran out
+    Expired,
+    /// A count-up reached its target
+    TargetReached,
+    /// A progress clock was filled
+    Filled,
+    /// A looping countdown ran out and reset to start another cycle
+    Looped,
+}
+
+impl TrackerCompletion {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TrackerCompletion::Expired => "expired",
+            TrackerCompletion::TargetReached => "target_reached",
+            TrackerCompletion::Filled => "filled",
+            TrackerCompletion::Looped => "looped",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "expired" => Some(TrackerCompletion::Expired),
+            "target_reached" => Some(TrackerCompletion::TargetReached),
+            "filled" => Some(TrackerCompletion::Filled),
+            "looped" => Some(TrackerCompletion::Looped),
+            _ => None,
+        }
+    }
+}
+
+/// Backend action run when a tracker completes
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompletionAction {
+    /// Make hidden entities visible to players
+    RevealEntities { entity_ids: Vec<String> },
+    /// Give the GM Fear (negative amounts spend it)
+    AddFear { amount: i32 },
+    /// Create entities from an adversary template
+    SpawnTemplate {
+        template_id: String,
+        #[serde(default = "default_spawn_count")]
+        count: u32,
+        #[serde(default)]
+        visible_to_players: bool,
+    },
+    /// Reset another tracker and start its auto-countdown, if it has one
+    StartTracker { tracker_id: String },
+    /// Reset another tracker to its starting value and stop it
+    ResetTracker { tracker_id: String },
+    /// Reveal one of this tracker's tick labels to players
+    RevealTickLabel { tick: i32 },
+}
+
+fn default_spawn_count() -> u32 {
+    1
+}
+
+impl CompletionAction {
+    /// Tracker this action chains to, if any
+    fn chained_tracker(&self) -> Option<&str> {
+        match self {
+            CompletionAction::StartTracker { tracker_id } | CompletionAction::ResetTracker { tracker_id } => {
+                Some(tracker_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// One edge in a campaign's tracker chain graph
+#[derive(Clone, Serialize, Debug)]
+pub struct TrackerChainLink {
+    pub from_tracker_id: String,
+    pub to_tracker_id: String,
+    pub action: CompletionAction,
+}
+
+/// What a tracker's completion changed, so it can be emitted after commit
+#[derive(Clone, Debug, Default)]
+pub struct CompletionEffects {
+    pub expired_conditions: Vec<Condition>,
+    pub fear_level: Option<i32>,
+    pub entities_changed: bool,
+}
+
+/// Scheduler state of a tracker's auto-countdown
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoCountdownState {
+    #[default]
+    Stopped,
+    Running,
+    Paused,
+}
+
+impl AutoCountdownState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AutoCountdownState::Stopped => "stopped",
+            AutoCountdownState::Running => "running",
+            AutoCountdownState::Paused => "paused",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "running" => AutoCountdownState::Running,
+            "paused" => AutoCountdownState::Paused,
+            _ => AutoCountdownState::Stopped,
+        }
+    }
+}
+
 #[derive(Clone, Serialize)]
 struct TrackersPayload {
     trackers: Vec<CountdownTracker>,
     campaign_id: String,
 }
 
+/// Payload for the countdown-completed event consumed by the alert window
+#[derive(Clone, Serialize)]
+struct CountdownCompletedPayload {
+    tracker: CountdownTracker,
+    completion: TrackerCompletion,
+    /// Cycles completed at once (only above 1 when a looping countdown wraps more than once)
+    loops: i32,
+    tick_label: Option<String>,
+    /// The GM asked to be alerted (auto-countdowns with `notify_on_complete`)
+    notify: bool,
+    campaign_id: String,
+}
+
+/// A dynamic countdown advanced by a duality roll
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TrackerAdvance {
+    pub id: String,
+    pub tracker_id: String,
+    pub campaign_id: String,
+    pub dice_roll_id: Option<String>,
+    pub outcome: DualityOutcome,
+    pub amount: i32,
+    pub value_before: i32,
+    pub value_after: i32,
+    pub completion: Option<TrackerCompletion>,
+    pub created_at: String,
+    /// Effects of a completion, emitted once the roll is committed
+    #[serde(skip)]
+    pub effects: Option<CompletionEffects>,
+    /// Times the advance completed the tracker (a looping tracker can wrap
+    /// more than once)
+    #[serde(skip)]
+    pub completions: i32,
+}
+
 // ============================================================================
 // Database Helpers
 // ============================================================================
 
+const TRACKER_COLUMNS: &str = "id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, \
+    tracker_type, auto_interval, notify_on_complete, auto_state, started_at, next_tick_at, paused_remaining_ms, \
+    linked_to_rolls, mode, cycle";
+
 fn row_to_tracker(row: &Row) -> rusqlite::Result<CountdownTracker> {
     Ok(CountdownTracker {
         id: row.get(0)?,
@@ -81,6 +322,16 @@ fn row_to_tracker(row: &Row) -> rusqlite::Result<CountdownTracker> {
         auto_interval: row.get(8)?,
         notify_on_complete: row.get::<_, i32>(9).unwrap_or(1) != 0,
         tick_labels: None,
+        auto_state: AutoCountdownState::from_str(&row.get::<_, String>(10)?),
+        started_at: row.get(11)?,
+        next_tick_at: row.get(12)?,
+        paused_remaining_ms: row.get(13)?,
+        linked_to_rolls: row.get::<_, i32>(14)? != 0,
+        advancement: HashMap::new(),
+        mode: TrackerMode::from_str(&row.get::<_, String>(15)?),
+        cycle: row.get(16)?,
+        revealed_ticks: Vec::new(),
+        completion_actions: Vec::new(),
     })
 }
 
@@ -94,10 +345,56 @@ fn get_tick_labels(conn: &Connection, tracker_id: &str) -> AppResult<HashMap<i32
     Ok(labels)
 }
 
+fn get_advancement(conn: &Connection, tracker_id: &str) -> AppResult<HashMap<DualityOutcome, i32>> {
+    let mut stmt = conn.prepare("SELECT outcome, amount FROM tracker_advancement WHERE tracker_id = ?1")?;
+
+    let rows = stmt
+        .query_map([tracker_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(outcome, amount)| DualityOutcome::from_str(&outcome).map(|o| (o, amount)))
+        .collect())
+}
+
+fn get_revealed_ticks(conn: &Connection, tracker_id: &str) -> AppResult<Vec<i32>> {
+    let mut stmt = conn.prepare("SELECT tick FROM tick_labels WHERE tracker_id = ?1 AND revealed = 1 ORDER BY tick")?;
+
+    let ticks = stmt
+        .query_map([tracker_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ticks)
+}
+
+fn get_completion_actions(conn: &Connection, tracker_id: &str) -> AppResult<Vec<CompletionAction>> {
+    let mut stmt = conn.prepare("SELECT action FROM tracker_actions WHERE tracker_id = ?1 ORDER BY position")?;
+
+    let rows = stmt
+        .query_map([tracker_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.iter()
+        .map(|json| serde_json::from_str(json).map_err(|e| AppError::PersistenceError(e.to_string())))
+        .collect()
+}
+
+/// Load the tick labels, roll advancement table and completion actions stored
+/// beside a tracker
+fn attach_tracker_details(conn: &Connection, tracker: &mut CountdownTracker) -> AppResult<()> {
+    if tracker.tracker_type == TrackerType::Complex {
+        tracker.tick_labels = Some(get_tick_labels(conn, &tracker.id)?);
+        tracker.revealed_ticks = get_revealed_ticks(conn, &tracker.id)?;
+    }
+    tracker.advancement = get_advancement(conn, &tracker.id)?;
+    tracker.completion_actions = get_completion_actions(conn, &tracker.id)?;
+    Ok(())
+}
+
 fn get_trackers_for_campaign(conn: &Connection, campaign_id: &str) -> AppResult<Vec<CountdownTracker>> {
     let mut stmt = conn.prepare(
-        "SELECT id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete
-         FROM countdown_trackers WHERE campaign_id = ?1",
+        &format!("SELECT {} FROM countdown_trackers WHERE campaign_id = ?1", TRACKER_COLUMNS),
     )?;
 
     let mut trackers: Vec<CountdownTracker> = stmt
@@ -105,27 +402,22 @@ fn get_trackers_for_campaign(conn: &Connection, campaign_id: &str) -> AppResult<
         .collect::<Result<Vec<_>, _>>()?;
 
     for tracker in &mut trackers {
-        if tracker.tracker_type == TrackerType::Complex {
-            tracker.tick_labels = Some(get_tick_labels(conn, &tracker.id)?);
-        }
+        attach_tracker_details(conn, tracker)?;
     }
 
     Ok(trackers)
 }
 
-fn get_tracker_by_id(conn: &Connection, id: &str) -> AppResult<CountdownTracker> {
+pub fn get_tracker_by_id(conn: &Connection, id: &str) -> AppResult<CountdownTracker> {
     let mut stmt = conn.prepare(
-        "SELECT id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete
-         FROM countdown_trackers WHERE id = ?1",
+        &format!("SELECT {} FROM countdown_trackers WHERE id = ?1", TRACKER_COLUMNS),
     )?;
 
     let mut tracker = stmt
         .query_row([id], |row| row_to_tracker(row))
         .map_err(|_| AppError::TrackerNotFound(id.to_string()))?;
 
-    if tracker.tracker_type == TrackerType::Complex {
-        tracker.tick_labels = Some(get_tick_labels(conn, id)?);
-    }
+    attach_tracker_details(conn, &mut tracker)?;
 
     Ok(tracker)
 }
@@ -156,30 +448,34 @@ pub fn create_tracker(
     hide_name_from_players: Option<bool>,
     auto_interval: Option<i32>,
     notify_on_complete: Option<bool>,
+    mode: Option<TrackerMode>,
 ) -> AppResult<CountdownTracker> {
     let id = Uuid::new_v4().to_string();
     let visible = visible_to_players.unwrap_or(false);
     let hide_name = hide_name_from_players.unwrap_or(false);
     let interval = auto_interval.unwrap_or(0);
     let notify = notify_on_complete.unwrap_or(true);
+    let mode = mode.unwrap_or_default();
+    let current = mode.start_value(max);
 
     db.with_conn(|conn| {
         let campaign_id = get_required_campaign_id(conn)?;
 
         conn.execute(
-            "INSERT INTO countdown_trackers (id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO countdown_trackers (id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete, mode)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 id,
                 campaign_id,
                 name,
-                max,
+                current,
                 max,
                 visible as i32,
                 hide_name as i32,
                 tracker_type.as_str(),
                 interval,
-                notify as i32
+                notify as i32,
+                mode.as_str()
             ],
         )?;
 
@@ -187,7 +483,7 @@ pub fn create_tracker(
             id,
             campaign_id: campaign_id.clone(),
             name,
-            current: max,
+            current,
             max,
             visible_to_players: visible,
             hide_name_from_players: hide_name,
@@ -199,6 +495,16 @@ pub fn create_tracker(
             } else {
                 None
             },
+            auto_state: AutoCountdownState::Stopped,
+            started_at: None,
+            next_tick_at: None,
+            paused_remaining_ms: None,
+            linked_to_rolls: false,
+            advancement: HashMap::new(),
+            mode,
+            cycle: 0,
+            revealed_ticks: Vec::new(),
+            completion_actions: Vec::new(),
         };
 
         emit_trackers_update(&app, conn, &campaign_id)?;
@@ -206,20 +512,186 @@ pub fn create_tracker(
     })
 }
 
+/// What to do with tick labels beyond a lowered `max`
+#[derive(Clone, Copy, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutOfRangeLabels {
+    /// Keep them stored but unused, and warn about them
+    #[default]
+    Keep,
+    /// Delete them
+    Prune,
+    /// Move them down onto the highest free ticks, keeping their order
+    Migrate,
+}
+
+/// Fields to change on a tracker; anything left out stays as it is
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateTracker {
+    pub name: Option<String>,
+    pub max: Option<i32>,
+    pub tracker_type: Option<TrackerType>,
+    #[serde(default)]
+    pub out_of_range_labels: OutOfRangeLabels,
+}
+
+/// An edited tracker and anything the GM should know about its labels
+#[derive(Clone, Serialize, Debug)]
+pub struct TrackerUpdateResult {
+    pub tracker: CountdownTracker,
+    pub warnings: Vec<String>,
+}
+
+/// Reconcile stored tick labels with a new `max`, returning warnings
+fn reconcile_tick_labels(
+    conn: &Connection,
+    tracker_id: &str,
+    max: i32,
+    policy: OutOfRangeLabels,
+) -> AppResult<Vec<String>> {
+    let labels = get_tick_labels(conn, tracker_id)?;
+    let mut out_of_range: Vec<i32> = labels.keys().copied().filter(|tick| *tick > max).collect();
+    out_of_range.sort_unstable_by(|a, b| b.cmp(a));
+    if out_of_range.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let describe = |ticks: &[i32]| {
+        ticks
+            .iter()
+            .map(|tick| format!("{} (\"{}\")", tick, labels[tick]))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut warnings = Vec::new();
+    match policy {
+        OutOfRangeLabels::Keep => {
+            warnings.push(format!(
+                "Tick labels beyond the new maximum are kept but unused: {}",
+                describe(&out_of_range)
+            ));
+        }
+        OutOfRangeLabels::Prune => {
+            for tick in &out_of_range {
+                conn.execute(
+                    "DELETE FROM tick_labels WHERE tracker_id = ?1 AND tick = ?2",
+                    params![tracker_id, tick],
+                )?;
+            }
+        }
+        OutOfRangeLabels::Migrate => {
+            let mut free = (0..=max).rev().filter(|tick| !labels.contains_key(tick));
+            let mut dropped = Vec::new();
+            for tick in &out_of_range {
+                match free.next() {
+                    Some(target) => {
+                        conn.execute(
+                            "UPDATE tick_labels SET tick = ?1 WHERE tracker_id = ?2 AND tick = ?3",
+                            params![target, tracker_id, tick],
+                        )?;
+                    }
+                    None => dropped.push(*tick),
+                }
+            }
+            if !dropped.is_empty() {
+                warnings.push(format!(
+                    "No free ticks left for these labels, so they are kept but unused: {}",
+                    describe(&dropped)
+                ));
+            }
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Rename a tracker, change its maximum or switch it between simple and
+/// complex. `current` is re-clamped to the new range (without counting as a
+/// completion) and labels beyond a lowered maximum are handled per
+/// `out_of_range_labels`. Switching to simple hides labels rather than
+/// deleting them, so switching back restores them.
+#[tauri::command]
+pub fn update_tracker(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    data: UpdateTracker,
+) -> AppResult<TrackerUpdateResult> {
+    if data.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::Validation("Tracker name cannot be empty".to_string()));
+    }
+    if data.max.is_some_and(|max| max < 1) {
+        return Err(AppError::Validation("Maximum value must be at least 1".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let tracker = get_tracker_by_id(conn, &id)?;
+
+        let name = data.name.map(|n| n.trim().to_string()).unwrap_or(tracker.name.clone());
+        let max = data.max.unwrap_or(tracker.max);
+        let tracker_type = data.tracker_type.unwrap_or(tracker.tracker_type.clone());
+        let current = match tracker.mode {
+            TrackerMode::CountUp => tracker.current.max(0),
+            _ => tracker.current.clamp(0, max),
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE countdown_trackers SET name = ?1, max = ?2, tracker_type = ?3, current = ?4 WHERE id = ?5",
+            params![name, max, tracker_type.as_str(), current, id],
+        )?;
+
+        let mut warnings = reconcile_tick_labels(&tx, &id, max, data.out_of_range_labels)?;
+        if tracker_type == TrackerType::Simple && tracker.tracker_type == TrackerType::Complex {
+            let hidden: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM tick_labels WHERE tracker_id = ?1",
+                [&id],
+                |row| row.get(0),
+            )?;
+            if hidden > 0 {
+                warnings.push(format!(
+                    "{} tick label(s) are hidden while the tracker is simple; switch back to complex to restore them",
+                    hidden
+                ));
+            }
+        }
+        tx.commit()?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(TrackerUpdateResult {
+            tracker: updated_tracker,
+            warnings,
+        })
+    })
+}
+
 #[tauri::command]
 pub fn delete_tracker(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<()> {
     db.with_conn(|conn| {
         let tracker = get_tracker_by_id(conn, &id)?;
         let campaign_id = tracker.campaign_id.clone();
 
-        conn.execute("DELETE FROM tick_labels WHERE tracker_id = ?1", [&id])?;
-        let rows_affected = conn.execute("DELETE FROM countdown_trackers WHERE id = ?1", [&id])?;
+        let tx = conn.unchecked_transaction()?;
+        // Conditions waiting on this countdown now last until removed
+        let orphaned = tx.execute(
+            "UPDATE conditions SET duration_type = 'indefinite', countdown_id = NULL WHERE countdown_id = ?1",
+            [&id],
+        )?;
+        remove_chains_to(&tx, &campaign_id, &id)?;
+        tx.execute("DELETE FROM tick_labels WHERE tracker_id = ?1", [&id])?;
+        let rows_affected = tx.execute("DELETE FROM countdown_trackers WHERE id = ?1", [&id])?;
 
         if rows_affected == 0 {
             return Err(AppError::TrackerNotFound(id));
         }
+        tx.commit()?;
 
         emit_trackers_update(&app, conn, &campaign_id)?;
+        if orphaned > 0 {
+            emit_entities_update(&app, conn, &campaign_id)?;
+        }
         Ok(())
     })
 }
@@ -230,15 +702,15 @@ pub fn get_trackers(db: State<Database>, visible_only: bool) -> AppResult<Vec<Co
         let campaign_id = get_required_campaign_id(conn)?;
 
         let mut stmt = if visible_only {
-            conn.prepare(
-                "SELECT id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete
-                 FROM countdown_trackers WHERE campaign_id = ?1 AND visible_to_players = 1",
-            )?
+            conn.prepare(&format!(
+                "SELECT {} FROM countdown_trackers WHERE campaign_id = ?1 AND visible_to_players = 1",
+                TRACKER_COLUMNS
+            ))?
         } else {
-            conn.prepare(
-                "SELECT id, campaign_id, name, current, max, visible_to_players, hide_name_from_players, tracker_type, auto_interval, notify_on_complete
-                 FROM countdown_trackers WHERE campaign_id = ?1",
-            )?
+            conn.prepare(&format!(
+                "SELECT {} FROM countdown_trackers WHERE campaign_id = ?1",
+                TRACKER_COLUMNS
+            ))?
         };
 
         let mut trackers: Vec<CountdownTracker> = stmt
@@ -246,9 +718,7 @@ pub fn get_trackers(db: State<Database>, visible_only: bool) -> AppResult<Vec<Co
             .collect::<Result<Vec<_>, _>>()?;
 
         for tracker in &mut trackers {
-            if tracker.tracker_type == TrackerType::Complex {
-                tracker.tick_labels = Some(get_tick_labels(conn, &tracker.id)?);
-            }
+            attach_tracker_details(conn, tracker)?;
         }
 
         Ok(trackers)
@@ -259,6 +729,94 @@ pub fn get_trackers(db: State<Database>, visible_only: bool) -> AppResult<Vec<Co
 // Value Management
 // ============================================================================
 
+/// Result of moving a tracker's value
+pub struct TrackerStep {
+    pub value: i32,
+    pub cycle: i32,
+    /// Times the tracker completed (only above 1 when a looping countdown wraps repeatedly)
+    pub completions: i32,
+}
+
+impl CountdownTracker {
+    /// Move `current` by a raw `delta` following the tracker's mode
+    pub fn step(&self, delta: i32) -> TrackerStep {
+        let (current, max) = (self.current, self.max);
+        let raw = current.saturating_add(delta);
+
+        let (value, completions) = match self.mode {
+            TrackerMode::Countdown => {
+                let value = raw.clamp(0, max);
+                (value, (current > 0 && value == 0) as i32)
+            }
+            TrackerMode::Progress => {
+                let value = raw.clamp(0, max);
+                (value, (current < max && value == max) as i32)
+            }
+            TrackerMode::CountUp => {
+                let value = raw.max(0);
+                (value, (current < max && value >= max) as i32)
+            }
+            // Ticks past 0 carry over into the next cycle
+            TrackerMode::Looping if delta < 0 && raw <= 0 && max > 0 => {
+                let (overshoot, period) = (raw.unsigned_abs(), max.unsigned_abs());
+                let loops = i32::try_from(overshoot / period).unwrap_or(i32::MAX);
+                (max - (overshoot % period) as i32, loops.saturating_add(1))
+            }
+            TrackerMode::Looping => (raw.clamp(0, max), 0),
+        };
+
+        let cycle = if self.mode == TrackerMode::Looping { self.cycle.saturating_add(completions) } else { self.cycle };
+        TrackerStep { value, cycle, completions }
+    }
+
+    /// Move `ticks` toward completion
+    pub fn advance(&self, ticks: i32) -> TrackerStep {
+        self.step(self.mode.direction().saturating_mul(ticks))
+    }
+
+    /// Whether there is nothing left to advance
+    pub fn is_complete(&self) -> bool {
+        match self.mode {
+            TrackerMode::Countdown => self.current <= 0,
+            TrackerMode::CountUp | TrackerMode::Progress => self.current >= self.max,
+            TrackerMode::Looping => self.max <= 0,
+        }
+    }
+}
+
+/// Persist a step's value and cycle
+fn apply_step(conn: &Connection, tracker: &CountdownTracker, step: &TrackerStep) -> AppResult<CountdownTracker> {
+    conn.execute(
+        "UPDATE countdown_trackers SET current = ?1, cycle = ?2 WHERE id = ?3",
+        params![step.value, step.cycle, tracker.id],
+    )?;
+
+    Ok(CountdownTracker {
+        current: step.value,
+        cycle: step.cycle,
+        ..tracker.clone()
+    })
+}
+
+/// Persist a step and, if it completed the tracker, run its completion
+/// inside one transaction. Returns the stored tracker and completion effects.
+fn commit_step(
+    conn: &Connection,
+    tracker: &CountdownTracker,
+    step: &TrackerStep,
+) -> AppResult<(CountdownTracker, Option<CompletionEffects>)> {
+    let tx = conn.unchecked_transaction()?;
+    let updated = apply_step(&tx, tracker, step)?;
+    let effects = if step.completions > 0 {
+        Some(run_completion(&tx, &updated)?)
+    } else {
+        None
+    };
+    tx.commit()?;
+
+    Ok((get_tracker_by_id(conn, &tracker.id)?, effects))
+}
+
 #[tauri::command]
 pub fn update_tracker_value(
     db: State<Database>,
@@ -268,19 +826,13 @@ pub fn update_tracker_value(
 ) -> AppResult<CountdownTracker> {
     db.with_conn(|conn| {
         let tracker = get_tracker_by_id(conn, &id)?;
-        let new_value = (tracker.current + amount).clamp(0, tracker.max);
-
-        conn.execute(
-            "UPDATE countdown_trackers SET current = ?1 WHERE id = ?2",
-            params![new_value, id],
-        )?;
-
-        let updated_tracker = CountdownTracker {
-            current: new_value,
-            ..tracker
-        };
+        let step = tracker.step(amount);
+        let (updated_tracker, effects) = commit_step(conn, &tracker, &step)?;
 
         emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        if let Some(effects) = &effects {
+            emit_completion(&app, conn, &updated_tracker, step.completions, false, effects)?;
+        }
         Ok(updated_tracker)
     })
 }
@@ -294,19 +846,13 @@ pub fn set_tracker_value(
 ) -> AppResult<CountdownTracker> {
     db.with_conn(|conn| {
         let tracker = get_tracker_by_id(conn, &id)?;
-        let new_value = value.clamp(0, tracker.max);
-
-        conn.execute(
-            "UPDATE countdown_trackers SET current = ?1 WHERE id = ?2",
-            params![new_value, id],
-        )?;
-
-        let updated_tracker = CountdownTracker {
-            current: new_value,
-            ..tracker
-        };
+        let step = tracker.step(value.saturating_sub(tracker.current));
+        let (updated_tracker, effects) = commit_step(conn, &tracker, &step)?;
 
         emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        if let Some(effects) = &effects {
+            emit_completion(&app, conn, &updated_tracker, step.completions, false, effects)?;
+        }
         Ok(updated_tracker)
     })
 }
@@ -418,7 +964,8 @@ pub fn set_tick_label(
         }
 
         conn.execute(
-            "INSERT OR REPLACE INTO tick_labels (tracker_id, tick, label) VALUES (?1, ?2, ?3)",
+            "INSERT INTO tick_labels (tracker_id, tick, label) VALUES (?1, ?2, ?3)
+             ON CONFLICT(tracker_id, tick) DO UPDATE SET label = excluded.label",
             params![id, tick, text],
         )?;
 
@@ -450,3 +997,728 @@ pub fn remove_tick_label(
         Ok(updated_tracker)
     })
 }
+
+// ============================================================================
+// Auto-Countdown Scheduler
+// ============================================================================
+
+/// How often the scheduler checks for due ticks
+const SCHEDULER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// A tracker advanced by the scheduler
+pub struct AutoTick {
+    pub tracker: CountdownTracker,
+    pub completions: i32,
+    pub effects: Option<CompletionEffects>,
+}
+
+/// Advance every running tracker whose next tick is due at `now`, each in
+/// its own transaction with its completion actions. Ticks missed while the
+/// app was closed are caught up in a single step. A tracker whose tick fails
+/// is stopped so it isn't retried on every pass.
+pub fn advance_due_trackers(conn: &Connection, now: i64) -> AppResult<Vec<AutoTick>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM countdown_trackers WHERE auto_state = 'running' AND next_tick_at <= ?1",
+    )?;
+    let due = stmt
+        .query_map([now], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut ticks = Vec::new();
+    for id in due {
+        // An earlier tracker's completion actions may have reset or restarted this one
+        let tracker = get_tracker_by_id(conn, &id)?;
+        if tracker.auto_state != AutoCountdownState::Running || !matches!(tracker.next_tick_at, Some(at) if at <= now) {
+            continue;
+        }
+
+        match advance_due_tracker(conn, &tracker, now) {
+            Ok(tick) => ticks.push(tick),
+            Err(e) => {
+                eprintln!("Auto-countdown '{}' stopped: {}", tracker.name, e);
+                conn.execute(
+                    "UPDATE countdown_trackers SET auto_state = 'stopped', next_tick_at = NULL WHERE id = ?1",
+                    [&tracker.id],
+                )?;
+            }
+        }
+    }
+
+    Ok(ticks)
+}
+
+fn advance_due_tracker(conn: &Connection, tracker: &CountdownTracker, now: i64) -> AppResult<AutoTick> {
+    let interval_ms = i64::from(tracker.auto_interval) * 1000;
+    let next_tick_at = tracker.next_tick_at.unwrap_or(now);
+    let elapsed_ticks = if interval_ms > 0 { 1 + (now - next_tick_at) / interval_ms } else { 0 };
+    let step = tracker.advance(elapsed_ticks.min(i64::from(i32::MAX)) as i32);
+
+    let tx = conn.unchecked_transaction()?;
+    let updated = apply_step(&tx, tracker, &step)?;
+
+    // Looping countdowns keep running; everything else stops once complete
+    let finished = interval_ms <= 0
+        || updated.is_complete()
+        || (step.completions > 0 && tracker.mode != TrackerMode::Looping);
+
+    if finished {
+        tx.execute(
+            "UPDATE countdown_trackers SET auto_state = 'stopped', next_tick_at = NULL, paused_remaining_ms = NULL WHERE id = ?1",
+            params![tracker.id],
+        )?;
+    } else {
+        tx.execute(
+            "UPDATE countdown_trackers SET next_tick_at = ?1 WHERE id = ?2",
+            params![next_tick_at + elapsed_ticks * interval_ms, tracker.id],
+        )?;
+    }
+
+    let effects = if step.completions > 0 {
+        Some(run_completion(&tx, &updated)?)
+    } else {
+        None
+    };
+    tx.commit()?;
+
+    Ok(AutoTick {
+        tracker: get_tracker_by_id(conn, &tracker.id)?,
+        completions: step.completions,
+        effects,
+    })
+}
+
+fn run_scheduler_pass(app: &tauri::AppHandle, conn: &Connection) -> AppResult<()> {
+    let ticks = advance_due_trackers(conn, now_ms())?;
+
+    let mut campaign_ids: Vec<&str> = Vec::new();
+    for tick in &ticks {
+        if !campaign_ids.contains(&tick.tracker.campaign_id.as_str()) {
+            campaign_ids.push(&tick.tracker.campaign_id);
+        }
+    }
+    for campaign_id in campaign_ids {
+        emit_trackers_update(app, conn, campaign_id)?;
+    }
+
+    for tick in &ticks {
+        if let Some(effects) = &tick.effects {
+            emit_completion(app, conn, &tick.tracker, tick.completions, tick.tracker.notify_on_complete, effects)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Spawn the background task that ticks running auto-countdowns.
+/// Trackers left running when the app closed resume from their persisted timestamps.
+pub fn spawn_auto_countdown_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SCHEDULER_POLL_INTERVAL);
+
+        let db = app.state::<Database>();
+        if let Err(e) = db.with_conn(|conn| run_scheduler_pass(&app, conn)) {
+            eprintln!("Auto-countdown scheduler error: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn start_auto_countdown(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<CountdownTracker> {
+    db.with_conn(|conn| {
+        let tracker = get_tracker_by_id(conn, &id)?;
+
+        if tracker.auto_interval <= 0 {
+            return Err(AppError::InvalidOperation(
+                "Tracker has no auto-countdown interval".to_string(),
+            ));
+        }
+        if tracker.auto_state == AutoCountdownState::Running {
+            return Err(AppError::InvalidOperation("Auto-countdown is already running".to_string()));
+        }
+        if tracker.is_complete() {
+            return Err(AppError::InvalidOperation("Countdown has already completed".to_string()));
+        }
+
+        let now = now_ms();
+        conn.execute(
+            "UPDATE countdown_trackers
+             SET auto_state = ?1, started_at = ?2, next_tick_at = ?3, paused_remaining_ms = NULL
+             WHERE id = ?4",
+            params![
+                AutoCountdownState::Running.as_str(),
+                now,
+                now + i64::from(tracker.auto_interval) * 1000,
+                id
+            ],
+        )?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(updated_tracker)
+    })
+}
+
+#[tauri::command]
+pub fn pause_auto_countdown(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<CountdownTracker> {
+    db.with_conn(|conn| {
+        let tracker = get_tracker_by_id(conn, &id)?;
+
+        if tracker.auto_state != AutoCountdownState::Running {
+            return Err(AppError::InvalidOperation("Auto-countdown is not running".to_string()));
+        }
+
+        let now = now_ms();
+        let remaining = tracker.next_tick_at.map_or(0, |at| (at - now).max(0));
+        conn.execute(
+            "UPDATE countdown_trackers SET auto_state = ?1, next_tick_at = NULL, paused_remaining_ms = ?2 WHERE id = ?3",
+            params![AutoCountdownState::Paused.as_str(), remaining, id],
+        )?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(updated_tracker)
+    })
+}
+
+#[tauri::command]
+pub fn resume_auto_countdown(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<CountdownTracker> {
+    db.with_conn(|conn| {
+        let tracker = get_tracker_by_id(conn, &id)?;
+
+        if tracker.auto_state != AutoCountdownState::Paused {
+            return Err(AppError::InvalidOperation("Auto-countdown is not paused".to_string()));
+        }
+        if tracker.is_complete() {
+            return Err(AppError::InvalidOperation("Countdown has already completed".to_string()));
+        }
+
+        let interval_ms = i64::from(tracker.auto_interval) * 1000;
+        let remaining = tracker.paused_remaining_ms.unwrap_or(interval_ms).min(interval_ms);
+        conn.execute(
+            "UPDATE countdown_trackers SET auto_state = ?1, next_tick_at = ?2, paused_remaining_ms = NULL WHERE id = ?3",
+            params![AutoCountdownState::Running.as_str(), now_ms() + remaining, id],
+        )?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(updated_tracker)
+    })
+}
+
+// ============================================================================
+// Dynamic Countdowns
+// ============================================================================
+
+fn row_to_tracker_advance(row: &Row) -> rusqlite::Result<TrackerAdvance> {
+    let outcome: String = row.get(4)?;
+    Ok(TrackerAdvance {
+        id: row.get(0)?,
+        tracker_id: row.get(1)?,
+        campaign_id: row.get(2)?,
+        dice_roll_id: row.get(3)?,
+        outcome: DualityOutcome::from_str(&outcome).unwrap_or(DualityOutcome::WithFear),
+        amount: row.get(5)?,
+        value_before: row.get(6)?,
+        value_after: row.get(7)?,
+        completion: row.get::<_, Option<String>>(8)?.and_then(|c| TrackerCompletion::from_str(&c)),
+        created_at: row.get(9)?,
+        effects: None,
+        completions: 0,
+    })
+}
+
+const ADVANCE_COLUMNS: &str =
+    "id, tracker_id, campaign_id, dice_roll_id, outcome, amount, value_before, value_after, completion, created_at";
+
+/// Advance every roll-linked countdown in the campaign by its amount for
+/// `outcome`, recording each advance, without emitting
+pub fn advance_linked_trackers(
+    conn: &Connection,
+    campaign_id: &str,
+    dice_roll_id: &str,
+    outcome: DualityOutcome,
+) -> AppResult<Vec<TrackerAdvance>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, a.amount
+         FROM countdown_trackers t
+         JOIN tracker_advancement a ON a.tracker_id = t.id
+         WHERE t.campaign_id = ?1 AND t.linked_to_rolls = 1 AND a.outcome = ?2 AND a.amount > 0",
+    )?;
+    let linked = stmt
+        .query_map(params![campaign_id, outcome.as_str()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // A tracker whose advance fails (e.g. a completion action that can no
+    // longer run) is skipped rather than failing the roll
+    let mut advances = Vec::new();
+    for (tracker_id, amount) in linked {
+        conn.execute_batch("SAVEPOINT linked_tracker")?;
+        match advance_linked_tracker(conn, &tracker_id, amount, dice_roll_id, outcome) {
+            Ok(advance) => {
+                conn.execute_batch("RELEASE linked_tracker")?;
+                advances.extend(advance);
+            }
+            Err(e) => {
+                eprintln!("Roll didn't advance tracker {}: {}", tracker_id, e);
+                conn.execute_batch("ROLLBACK TO linked_tracker; RELEASE linked_tracker")?;
+            }
+        }
+    }
+
+    Ok(advances)
+}
+
+fn advance_linked_tracker(
+    conn: &Connection,
+    tracker_id: &str,
+    amount: i32,
+    dice_roll_id: &str,
+    outcome: DualityOutcome,
+) -> AppResult<Option<TrackerAdvance>> {
+    let tracker = get_tracker_by_id(conn, tracker_id)?;
+    if tracker.is_complete() {
+        return Ok(None);
+    }
+
+    let step = tracker.advance(amount);
+    let updated = apply_step(conn, &tracker, &step)?;
+    let completion = (step.completions > 0).then(|| tracker.mode.completion());
+    let effects = match completion {
+        Some(_) => Some(run_completion(conn, &updated)?),
+        None => None,
+    };
+
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tracker_advances (id, tracker_id, campaign_id, dice_roll_id, outcome, amount, value_before, value_after, completion)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            tracker_id,
+            tracker.campaign_id,
+            dice_roll_id,
+            outcome.as_str(),
+            amount,
+            tracker.current,
+            step.value,
+            completion.map(|c| c.as_str())
+        ],
+    )?;
+
+    let mut advance = conn.query_row(
+        &format!("SELECT {} FROM tracker_advances WHERE id = ?1", ADVANCE_COLUMNS),
+        [&id],
+        row_to_tracker_advance,
+    )?;
+    advance.effects = effects;
+    advance.completions = step.completions;
+    Ok(Some(advance))
+}
+
+/// Emit the tracker changes from committed roll advances and complete any
+/// tracker they finished
+pub fn emit_tracker_advances(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    campaign_id: &str,
+    advances: &[TrackerAdvance],
+) -> AppResult<()> {
+    if advances.is_empty() {
+        return Ok(());
+    }
+
+    emit_trackers_update(app, conn, campaign_id)?;
+    for advance in advances {
+        if let Some(effects) = &advance.effects {
+            let tracker = get_tracker_by_id(conn, &advance.tracker_id)?;
+            emit_completion(app, conn, &tracker, advance.completions, false, effects)?;
+        }
+    }
+    Ok(())
+}
+
+/// Configure how far a dynamic countdown advances for each duality roll
+/// outcome, and whether incoming rolls advance it automatically
+#[tauri::command]
+pub fn set_tracker_advancement(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    advancement: HashMap<DualityOutcome, i32>,
+    linked_to_rolls: bool,
+) -> AppResult<CountdownTracker> {
+    if advancement.values().any(|amount| *amount < 0) {
+        return Err(AppError::Validation("Advancement amounts cannot be negative".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let _tracker = get_tracker_by_id(conn, &id)?;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM tracker_advancement WHERE tracker_id = ?1", [&id])?;
+        for (outcome, amount) in advancement.iter().filter(|(_, amount)| **amount > 0) {
+            tx.execute(
+                "INSERT INTO tracker_advancement (tracker_id, outcome, amount) VALUES (?1, ?2, ?3)",
+                params![id, outcome.as_str(), amount],
+            )?;
+        }
+        tx.execute(
+            "UPDATE countdown_trackers SET linked_to_rolls = ?1 WHERE id = ?2",
+            params![linked_to_rolls as i32, id],
+        )?;
+        tx.commit()?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(updated_tracker)
+    })
+}
+
+/// Rolls that advanced a dynamic countdown, newest first
+#[tauri::command]
+pub fn get_tracker_advances(
+    db: State<Database>,
+    tracker_id: String,
+    limit: Option<u32>,
+) -> AppResult<Vec<TrackerAdvance>> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tracker_advances WHERE tracker_id = ?1 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+            ADVANCE_COLUMNS
+        ))?;
+        let advances = stmt
+            .query_map(params![tracker_id, limit.unwrap_or(50)], row_to_tracker_advance)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(advances)
+    })
+}
+
+// ============================================================================
+// Completion Actions
+// ============================================================================
+
+/// Put a tracker back at its starting value, optionally starting its
+/// auto-countdown
+fn restart_tracker(conn: &Connection, tracker: &CountdownTracker, start: bool) -> AppResult<()> {
+    let now = now_ms();
+    let running = start && tracker.auto_interval > 0;
+
+    conn.execute(
+        "UPDATE countdown_trackers
+         SET current = ?1, cycle = 0, auto_state = ?2, started_at = ?3, next_tick_at = ?4, paused_remaining_ms = NULL
+         WHERE id = ?5",
+        params![
+            tracker.mode.start_value(tracker.max),
+            if running { AutoCountdownState::Running } else { AutoCountdownState::Stopped }.as_str(),
+            running.then_some(now),
+            running.then(|| now + i64::from(tracker.auto_interval) * 1000),
+            tracker.id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Clear conditions lasting until the tracker completes and run its
+/// completion actions, without emitting. Targets deleted since the actions
+/// were configured are skipped; any other failure aborts the caller's
+/// transaction so the completion applies all or nothing.
+fn run_completion(conn: &Connection, tracker: &CountdownTracker) -> AppResult<CompletionEffects> {
+    let mut effects = CompletionEffects {
+        expired_conditions: expire_countdown_conditions(conn, &tracker.id)?,
+        ..Default::default()
+    };
+
+    for action in &tracker.completion_actions {
+        match action {
+            CompletionAction::RevealEntities { entity_ids } => {
+                for entity_id in entity_ids {
+                    effects.entities_changed |= conn.execute(
+                        "UPDATE entities SET visible_to_players = 1 WHERE id = ?1 AND campaign_id = ?2",
+                        params![entity_id, tracker.campaign_id],
+                    )? > 0;
+                }
+            }
+            CompletionAction::AddFear { amount } => {
+                effects.fear_level = Some(apply_fear_adjustment(conn, &tracker.campaign_id, *amount)?);
+            }
+            CompletionAction::SpawnTemplate { template_id, count, visible_to_players } => {
+                if let Ok(template) = get_template_by_id(conn, template_id) {
+                    spawn_template_into_campaign(conn, &tracker.campaign_id, &template, *count, *visible_to_players)?;
+                    effects.entities_changed = true;
+                }
+            }
+            CompletionAction::StartTracker { tracker_id } | CompletionAction::ResetTracker { tracker_id } => {
+                if let Ok(target) = get_tracker_by_id(conn, tracker_id) {
+                    let start = matches!(action, CompletionAction::StartTracker { .. });
+                    restart_tracker(conn, &target, start)?;
+                }
+            }
+            CompletionAction::RevealTickLabel { tick } => {
+                conn.execute(
+                    "UPDATE tick_labels SET revealed = 1 WHERE tracker_id = ?1 AND tick = ?2",
+                    params![tracker.id, tick],
+                )?;
+            }
+        }
+    }
+
+    Ok(effects)
+}
+
+/// Emit everything a committed completion changed, then `countdown-completed`
+/// with the completion semantics of the tracker's mode
+fn emit_completion(
+    app: &tauri::AppHandle,
+    conn: &Connection,
+    tracker: &CountdownTracker,
+    loops: i32,
+    notify: bool,
+    effects: &CompletionEffects,
+) -> AppResult<()> {
+    emit_condition_changes(app, conn, &tracker.campaign_id, &effects.expired_conditions)?;
+    if let Some(level) = effects.fear_level {
+        emit_fear_update(app, level, &tracker.campaign_id)?;
+    }
+    if effects.entities_changed {
+        emit_entities_update(app, conn, &tracker.campaign_id)?;
+    }
+
+    let completion_tick = match tracker.mode {
+        TrackerMode::Countdown | TrackerMode::Looping => 0,
+        TrackerMode::CountUp | TrackerMode::Progress => tracker.max,
+    };
+    let tick_label = tracker.tick_labels.as_ref().and_then(|labels| labels.get(&completion_tick).cloned());
+
+    app.emit(
+        "countdown-completed",
+        CountdownCompletedPayload {
+            tracker: tracker.clone(),
+            completion: tracker.mode.completion(),
+            loops,
+            tick_label,
+            notify,
+            campaign_id: tracker.campaign_id.clone(),
+        },
+    )
+    .map_err(|e| AppError::EmitError(e.to_string()))
+}
+
+fn validate_completion_action(
+    conn: &Connection,
+    tracker: &CountdownTracker,
+    action: &CompletionAction,
+) -> AppResult<()> {
+    match action {
+        CompletionAction::RevealEntities { entity_ids } => {
+            if entity_ids.is_empty() {
+                return Err(AppError::Validation("Choose at least one entity to reveal".to_string()));
+            }
+            for entity_id in entity_ids {
+                let found: bool = conn.query_row(
+                    "SELECT COUNT(*) > 0 FROM entities WHERE id = ?1 AND campaign_id = ?2",
+                    params![entity_id, tracker.campaign_id],
+                    |row| row.get(0),
+                )?;
+                if !found {
+                    return Err(AppError::EntityNotFound(entity_id.clone()));
+                }
+            }
+        }
+        CompletionAction::AddFear { amount } => {
+            if *amount == 0 {
+                return Err(AppError::Validation("Fear amount cannot be 0".to_string()));
+            }
+        }
+        CompletionAction::SpawnTemplate { template_id, count, .. } => {
+            get_template_by_id(conn, template_id)?;
+            if *count == 0 || *count > MAX_SPAWN_COUNT {
+                return Err(AppError::Validation(format!(
+                    "Can spawn between 1 and {} instances at a time",
+                    MAX_SPAWN_COUNT
+                )));
+            }
+        }
+        CompletionAction::StartTracker { tracker_id } | CompletionAction::ResetTracker { tracker_id } => {
+            let target = get_tracker_by_id(conn, tracker_id)?;
+            if target.campaign_id != tracker.campaign_id {
+                return Err(AppError::InvalidOperation(
+                    "Cannot chain to a tracker in another campaign".to_string(),
+                ));
+            }
+        }
+        CompletionAction::RevealTickLabel { tick } => {
+            if tracker.tracker_type != TrackerType::Complex {
+                return Err(AppError::InvalidOperation(
+                    "Only complex trackers have tick labels to reveal".to_string(),
+                ));
+            }
+            if *tick < 0 || *tick > tracker.max {
+                return Err(AppError::OutOfRange(format!(
+                    "Tick {} out of range (0-{})",
+                    tick, tracker.max
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every Start/Reset chain between a campaign's trackers
+fn get_chain_links(conn: &Connection, campaign_id: &str) -> AppResult<Vec<TrackerChainLink>> {
+    let mut links = Vec::new();
+    for tracker in get_trackers_for_campaign(conn, campaign_id)? {
+        for action in tracker.completion_actions {
+            if let Some(target) = action.chained_tracker() {
+                links.push(TrackerChainLink {
+                    from_tracker_id: tracker.id.clone(),
+                    to_tracker_id: target.to_string(),
+                    action: action.clone(),
+                });
+            }
+        }
+    }
+    Ok(links)
+}
+
+/// Find a cycle in the chain graph, returned as the tracker ids along it
+/// with the first repeated at the end
+fn find_chain_cycle(links: &[TrackerChainLink]) -> Option<Vec<String>> {
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for link in links {
+        edges.entry(&link.from_tracker_id).or_default().push(&link.to_tracker_id);
+    }
+
+    // Depth-first search; a node still on the path when reached again closes a cycle
+    fn visit<'a>(
+        node: &'a str,
+        edges: &HashMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        done: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|n| *n == node) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(node.to_string());
+            return Some(cycle);
+        }
+        if done.contains(&node) {
+            return None;
+        }
+
+        path.push(node);
+        for next in edges.get(node).into_iter().flatten() {
+            if let Some(cycle) = visit(next, edges, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.push(node);
+        None
+    }
+
+    let mut done = Vec::new();
+    for node in edges.keys() {
+        if let Some(cycle) = visit(node, &edges, &mut Vec::new(), &mut done) {
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+fn write_completion_actions(conn: &Connection, tracker_id: &str, actions: &[CompletionAction]) -> AppResult<()> {
+    conn.execute("DELETE FROM tracker_actions WHERE tracker_id = ?1", [tracker_id])?;
+    for (position, action) in actions.iter().enumerate() {
+        let json = serde_json::to_string(action).map_err(|e| AppError::PersistenceError(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO tracker_actions (tracker_id, position, action) VALUES (?1, ?2, ?3)",
+            params![tracker_id, position as i64, json],
+        )?;
+    }
+    Ok(())
+}
+
+/// Drop the Start/Reset actions that chain other trackers to `tracker_id`,
+/// keeping their remaining actions in order
+fn remove_chains_to(conn: &Connection, campaign_id: &str, tracker_id: &str) -> AppResult<()> {
+    let mut sources: Vec<String> = get_chain_links(conn, campaign_id)?
+        .into_iter()
+        .filter(|link| link.to_tracker_id == tracker_id && link.from_tracker_id != tracker_id)
+        .map(|link| link.from_tracker_id)
+        .collect();
+    sources.dedup();
+
+    for source in sources {
+        let actions: Vec<CompletionAction> = get_completion_actions(conn, &source)?
+            .into_iter()
+            .filter(|action| action.chained_tracker() != Some(tracker_id))
+            .collect();
+        write_completion_actions(conn, &source, &actions)?;
+    }
+    Ok(())
+}
+
+/// Replace the actions a tracker runs, in order and in one transaction, when
+/// it completes. Start/Reset actions chain trackers together; a chain that
+/// would loop back on itself is rejected (use a looping tracker instead).
+#[tauri::command]
+pub fn set_tracker_actions(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    actions: Vec<CompletionAction>,
+) -> AppResult<CountdownTracker> {
+    db.with_conn(|conn| {
+        let tracker = get_tracker_by_id(conn, &id)?;
+        for action in &actions {
+            validate_completion_action(conn, &tracker, action)?;
+        }
+
+        let mut links: Vec<TrackerChainLink> = get_chain_links(conn, &tracker.campaign_id)?
+            .into_iter()
+            .filter(|link| link.from_tracker_id != id)
+            .collect();
+        links.extend(actions.iter().filter_map(|action| {
+            action.chained_tracker().map(|target| TrackerChainLink {
+                from_tracker_id: id.clone(),
+                to_tracker_id: target.to_string(),
+                action: action.clone(),
+            })
+        }));
+
+        if let Some(cycle) = find_chain_cycle(&links) {
+            let names = cycle
+                .iter()
+                .map(|tracker_id| get_tracker_by_id(conn, tracker_id).map(|t| t.name))
+                .collect::<AppResult<Vec<_>>>()?;
+            return Err(AppError::Validation(format!(
+                "Tracker chain would form a cycle: {}",
+                names.join(" → ")
+            )));
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        write_completion_actions(&tx, &id, &actions)?;
+        tx.commit()?;
+
+        let updated_tracker = get_tracker_by_id(conn, &id)?;
+        emit_trackers_update(&app, conn, &updated_tracker.campaign_id)?;
+        Ok(updated_tracker)
+    })
+}
+
+/// The current campaign's tracker chain graph, one link per Start/Reset action
+#[tauri::command]
+pub fn get_tracker_chains(db: State<Database>) -> AppResult<Vec<TrackerChainLink>> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        get_chain_links(conn, &campaign_id)
+    })
+}
@@ -114,6 +114,78 @@ fn run_migrations(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
         migrate_v11_countdown_notify_on_complete(conn)?;
     }
 
+    if current_version < 12 {
+        migrate_v12_dice_roll_outcomes(conn)?;
+    }
+
+    if current_version < 13 {
+        migrate_v13_secret_dice_rolls(conn)?;
+    }
+
+    if current_version < 14 {
+        migrate_v14_roll_macros_table(conn)?;
+    }
+
+    if current_version < 15 {
+        migrate_v15_dice_rng_sessions(conn)?;
+    }
+
+    if current_version < 16 {
+        migrate_v16_attack_results(conn)?;
+    }
+
+    if current_version < 17 {
+        migrate_v17_adversary_stat_blocks(conn)?;
+    }
+
+    if current_version < 18 {
+        migrate_v18_adversary_templates(conn)?;
+    }
+
+    if current_version < 19 {
+        migrate_v19_minions_and_hordes(conn)?;
+    }
+
+    if current_version < 20 {
+        migrate_v20_damage_types_and_armor(conn)?;
+    }
+
+    if current_version < 21 {
+        migrate_v21_conditions(conn)?;
+    }
+
+    if current_version < 22 {
+        migrate_v22_encounters(conn)?;
+    }
+
+    if current_version < 23 {
+        migrate_v23_spotlight(conn)?;
+    }
+
+    if current_version < 24 {
+        migrate_v24_entity_events(conn)?;
+    }
+
+    if current_version < 25 {
+        migrate_v25_auto_countdown_state(conn)?;
+    }
+
+    if current_version < 26 {
+        migrate_v26_dynamic_countdowns(conn)?;
+    }
+
+    if current_version < 27 {
+        migrate_v27_tracker_modes(conn)?;
+    }
+
+    if current_version < 28 {
+        migrate_v28_tracker_actions(conn)?;
+    }
+
+    if current_version < 29 {
+        migrate_v29_entity_event_parents(conn)?;
+    }
+
     Ok(())
 }
 
@@ -643,3 +715,896 @@ fn migrate_v11_countdown_notify_on_complete(conn: &Connection) -> Result<(), Box
 
     Ok(())
 }
+
+/// V12: Add roll type, duality outcome and roller columns to dice_rolls
+fn migrate_v12_dice_roll_outcomes(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('dice_rolls') WHERE name='roll_type'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN roll_type TEXT NOT NULL DEFAULT 'standard'",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN outcome TEXT",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN difficulty INTEGER",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN player_character_id TEXT REFERENCES player_characters(id) ON DELETE SET NULL",
+            [],
+        )?;
+        println!("Added roll outcome columns to dice_rolls table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (12)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V13: Add secret roll columns to dice_rolls
+fn migrate_v13_secret_dice_rolls(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('dice_rolls') WHERE name='is_secret'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN is_secret INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN revealed_at TEXT",
+            [],
+        )?;
+        println!("Added secret roll columns to dice_rolls table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (13)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V14: Create roll_macros table
+fn migrate_v14_roll_macros_table(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='roll_macros'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE roll_macros (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                player_character_id TEXT,
+                name TEXT NOT NULL,
+                notation TEXT NOT NULL,
+                roll_type TEXT NOT NULL DEFAULT 'standard',
+                difficulty INTEGER,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+                FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_roll_macros_campaign ON roll_macros(campaign_id)",
+            [],
+        )?;
+
+        println!("Created roll_macros table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (14)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V15: Create dice_rng_sessions table and link dice_rolls to the seed that produced them
+fn migrate_v15_dice_rng_sessions(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='dice_rng_sessions'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE dice_rng_sessions (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                mode TEXT NOT NULL DEFAULT 'committed',
+                seed TEXT NOT NULL,
+                seed_hash TEXT NOT NULL,
+                client_seed TEXT NOT NULL DEFAULT '',
+                next_nonce INTEGER NOT NULL DEFAULT 0,
+                started_at TEXT NOT NULL DEFAULT (datetime('now')),
+                ended_at TEXT,
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_dice_rng_sessions_campaign ON dice_rng_sessions(campaign_id)",
+            [],
+        )?;
+
+        println!("Created dice_rng_sessions table");
+    }
+
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('dice_rolls') WHERE name='rng_session_id'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN rng_session_id TEXT REFERENCES dice_rng_sessions(id) ON DELETE SET NULL",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN rng_nonce INTEGER",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE dice_rolls ADD COLUMN roll_options TEXT",
+            [],
+        )?;
+        println!("Added seed columns to dice_rolls table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (15)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V16: Entity Difficulty and attack results linking attack/damage rolls to a target
+fn migrate_v16_attack_results(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('entities') WHERE name='difficulty'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE entities ADD COLUMN difficulty INTEGER",
+            [],
+        )?;
+        println!("Added difficulty column to entities table");
+    }
+
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='attack_results'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE attack_results (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                attacker_type TEXT NOT NULL,
+                attacker_id TEXT NOT NULL,
+                target_entity_id TEXT NOT NULL,
+                attack_roll_id TEXT REFERENCES dice_rolls(id) ON DELETE SET NULL,
+                damage_roll_id TEXT REFERENCES dice_rolls(id) ON DELETE SET NULL,
+                difficulty INTEGER NOT NULL,
+                hit INTEGER NOT NULL DEFAULT 0,
+                critical INTEGER NOT NULL DEFAULT 0,
+                damage_total INTEGER NOT NULL DEFAULT 0,
+                hp_marked INTEGER NOT NULL DEFAULT 0,
+                threshold_hit TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_entity_id) REFERENCES entities(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_attack_results_campaign ON attack_results(campaign_id)",
+            [],
+        )?;
+
+        println!("Created attack_results table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (16)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V17: Adversary stat block columns on entities
+fn migrate_v17_adversary_stat_blocks(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('entities') WHERE name='tier'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        let columns = [
+            "tier INTEGER NOT NULL DEFAULT 1",
+            "role TEXT NOT NULL DEFAULT 'standard'",
+            "attack_modifier INTEGER NOT NULL DEFAULT 0",
+            "attack_name TEXT",
+            "attack_range TEXT",
+            "attack_damage TEXT",
+            "motives_and_tactics TEXT",
+            "experiences TEXT NOT NULL DEFAULT '[]'",
+            "features TEXT NOT NULL DEFAULT '[]'",
+        ];
+        for column in columns {
+            conn.execute(&format!("ALTER TABLE entities ADD COLUMN {}", column), [])?;
+        }
+        println!("Added stat block columns to entities table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (17)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V18: Global adversary template library (not scoped to a campaign)
+fn migrate_v18_adversary_templates(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='adversary_templates'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE adversary_templates (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                entity_type TEXT NOT NULL DEFAULT 'adversary',
+                hp_max INTEGER NOT NULL,
+                stress_max INTEGER NOT NULL DEFAULT 0,
+                threshold_minor INTEGER NOT NULL,
+                threshold_major INTEGER NOT NULL,
+                threshold_severe INTEGER NOT NULL,
+                difficulty INTEGER,
+                tier INTEGER NOT NULL DEFAULT 1,
+                role TEXT NOT NULL DEFAULT 'standard',
+                attack_modifier INTEGER NOT NULL DEFAULT 0,
+                attack_name TEXT,
+                attack_range TEXT,
+                attack_damage TEXT,
+                motives_and_tactics TEXT,
+                experiences TEXT NOT NULL DEFAULT '[]',
+                features TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_adversary_templates_name ON adversary_templates(name)",
+            [],
+        )?;
+
+        println!("Created adversary_templates table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (18)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V19: Minion groups, "Minion (X)" spillover and reduced Horde damage
+fn migrate_v19_minions_and_hordes(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    for table in ["entities", "adversary_templates"] {
+        let column_exists: bool = conn
+            .query_row(
+                &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name='minion_threshold'", table),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !column_exists {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN minion_threshold INTEGER", table), [])?;
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN horde_damage TEXT", table), [])?;
+            println!("Added minion and horde columns to {} table", table);
+        }
+    }
+
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('entities') WHERE name='group_id'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE entities ADD COLUMN group_id TEXT",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_entities_group ON entities(group_id)",
+            [],
+        )?;
+        println!("Added group_id column to entities table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (19)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V20: Physical/magic damage, resistances and Armor Slots for adversaries
+fn migrate_v20_damage_types_and_armor(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    for table in ["entities", "adversary_templates"] {
+        let column_exists: bool = conn
+            .query_row(
+                &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name='physical_affinity'", table),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !column_exists {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN physical_affinity TEXT NOT NULL DEFAULT 'normal'", table),
+                [],
+            )?;
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN magic_affinity TEXT NOT NULL DEFAULT 'normal'", table),
+                [],
+            )?;
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN armor_slots INTEGER NOT NULL DEFAULT 0", table), [])?;
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN attack_damage_type TEXT", table), [])?;
+            println!("Added damage type and armor columns to {} table", table);
+        }
+    }
+
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('entities') WHERE name='armor_marked'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE entities ADD COLUMN armor_marked INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        println!("Added armor_marked column to entities table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (20)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V21: Conditions on entities and player characters
+fn migrate_v21_conditions(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='conditions'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE conditions (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                entity_id TEXT,
+                player_character_id TEXT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                visible_to_players INTEGER NOT NULL DEFAULT 1,
+                duration_type TEXT NOT NULL DEFAULT 'indefinite',
+                spotlights_remaining INTEGER,
+                countdown_id TEXT,
+                tick_damage INTEGER NOT NULL DEFAULT 0,
+                tick_stress INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+                FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+                FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE,
+                FOREIGN KEY (countdown_id) REFERENCES countdown_trackers(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        conn.execute("CREATE INDEX idx_conditions_campaign ON conditions(campaign_id)", [])?;
+        conn.execute("CREATE INDEX idx_conditions_entity ON conditions(entity_id)", [])?;
+        conn.execute(
+            "CREATE INDEX idx_conditions_player_character ON conditions(player_character_id)",
+            [],
+        )?;
+
+        println!("Created conditions table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (21)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V22: Encounters and active player characters for Battle Point budgets
+fn migrate_v22_encounters(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('player_characters') WHERE name='is_active'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE player_characters ADD COLUMN is_active INTEGER NOT NULL DEFAULT 1",
+            [],
+        )?;
+        println!("Added is_active column to player_characters table");
+    }
+
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='encounters'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE encounters (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                notes TEXT,
+                intensity TEXT NOT NULL DEFAULT 'standard',
+                damage_boost INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'staged',
+                activated_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE encounter_entities (
+                entity_id TEXT PRIMARY KEY,
+                encounter_id TEXT NOT NULL,
+                FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+                FOREIGN KEY (encounter_id) REFERENCES encounters(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute("CREATE INDEX idx_encounters_campaign ON encounters(campaign_id)", [])?;
+        conn.execute(
+            "CREATE INDEX idx_encounter_entities_encounter ON encounter_entities(encounter_id)",
+            [],
+        )?;
+
+        println!("Created encounters tables");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (22)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V23: Spotlight tracking with optional action tokens
+fn migrate_v23_spotlight(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='spotlight_changes'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE spotlight_state (
+                campaign_id TEXT PRIMARY KEY,
+                scene INTEGER NOT NULL DEFAULT 1,
+                use_action_tokens INTEGER NOT NULL DEFAULT 0,
+                gm_tokens INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE spotlight_changes (
+                id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                scene INTEGER NOT NULL,
+                holder_type TEXT NOT NULL,
+                holder_id TEXT,
+                holder_name TEXT NOT NULL,
+                changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE action_tokens (
+                player_character_id TEXT PRIMARY KEY,
+                campaign_id TEXT NOT NULL,
+                tokens INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE,
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_spotlight_changes_scene ON spotlight_changes(campaign_id, scene)",
+            [],
+        )?;
+
+        println!("Created spotlight tables");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (23)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V24: Entity HP and Stress history
+fn migrate_v24_entity_events(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='entity_events'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE entity_events (
+                id TEXT PRIMARY KEY,
+                entity_id TEXT NOT NULL,
+                campaign_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                source TEXT NOT NULL,
+                raw_amount INTEGER NOT NULL,
+                damage_type TEXT,
+                threshold_hit TEXT,
+                hp_before INTEGER NOT NULL,
+                hp_after INTEGER NOT NULL,
+                stress_before INTEGER NOT NULL,
+                stress_after INTEGER NOT NULL,
+                armor_before INTEGER NOT NULL DEFAULT 0,
+                armor_after INTEGER NOT NULL DEFAULT 0,
+                undone INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_entity_events_entity ON entity_events(entity_id, created_at)",
+            [],
+        )?;
+
+        println!("Created entity_events table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (24)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V25: Persisted auto-countdown scheduler state
+fn migrate_v25_auto_countdown_state(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('countdown_trackers') WHERE name='auto_state'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE countdown_trackers ADD COLUMN auto_state TEXT NOT NULL DEFAULT 'stopped'",
+            [],
+        )?;
+        conn.execute("ALTER TABLE countdown_trackers ADD COLUMN started_at INTEGER", [])?;
+        conn.execute("ALTER TABLE countdown_trackers ADD COLUMN next_tick_at INTEGER", [])?;
+        conn.execute("ALTER TABLE countdown_trackers ADD COLUMN paused_remaining_ms INTEGER", [])?;
+        println!("Added auto-countdown state columns to countdown_trackers table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (25)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V26: Dynamic countdowns advanced by duality roll outcomes
+fn migrate_v26_dynamic_countdowns(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('countdown_trackers') WHERE name='linked_to_rolls'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE countdown_trackers ADD COLUMN linked_to_rolls INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        println!("Added linked_to_rolls column to countdown_trackers table");
+    }
+
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='tracker_advancement'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE tracker_advancement (
+                tracker_id TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                PRIMARY KEY (tracker_id, outcome),
+                FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        println!("Created tracker_advancement table");
+    }
+
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='tracker_advances'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE tracker_advances (
+                id TEXT PRIMARY KEY,
+                tracker_id TEXT NOT NULL,
+                campaign_id TEXT NOT NULL,
+                dice_roll_id TEXT,
+                outcome TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                value_before INTEGER NOT NULL,
+                value_after INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE,
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+                FOREIGN KEY (dice_roll_id) REFERENCES dice_rolls(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX idx_tracker_advances_tracker ON tracker_advances(tracker_id, created_at)",
+            [],
+        )?;
+
+        println!("Created tracker_advances table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (26)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V27: Count-up, progress and looping tracker modes
+fn migrate_v27_tracker_modes(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('countdown_trackers') WHERE name='mode'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE countdown_trackers ADD COLUMN mode TEXT NOT NULL DEFAULT 'countdown'",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE countdown_trackers ADD COLUMN cycle INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        println!("Added mode and cycle columns to countdown_trackers table");
+    }
+
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('tracker_advances') WHERE name='completion'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute("ALTER TABLE tracker_advances ADD COLUMN completion TEXT", [])?;
+        println!("Added completion column to tracker_advances table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (27)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V28: Tracker completion actions and revealed tick labels
+fn migrate_v28_tracker_actions(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='tracker_actions'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !table_exists {
+        conn.execute(
+            "CREATE TABLE tracker_actions (
+                tracker_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                PRIMARY KEY (tracker_id, position),
+                FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        println!("Created tracker_actions table");
+    }
+
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('tick_labels') WHERE name='revealed'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute(
+            "ALTER TABLE tick_labels ADD COLUMN revealed INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        println!("Added revealed column to tick_labels table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (28)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// V29: Link Minion spillover events to the hit that caused them
+fn migrate_v29_entity_event_parents(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let column_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('entity_events') WHERE name='parent_event_id'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !column_exists {
+        conn.execute("ALTER TABLE entity_events ADD COLUMN parent_event_id TEXT", [])?;
+        println!("Added parent_event_id column to entity_events table");
+    }
+
+    conn.execute(
+        "INSERT INTO schema_migrations (version) VALUES (29)",
+        [],
+    )?;
+
+    Ok(())
+}
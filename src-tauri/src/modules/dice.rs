@@ -1,31 +1,206 @@
-use rusqlite::{params, Row};
+use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use super::countdown::{advance_linked_trackers, emit_tracker_advances, TrackerAdvance};
 use super::database::Database;
-use super::error::AppResult;
+use super::dice_engine::{self, DieResult, DualityOutcome, RollOptions, RollResult};
+use super::dice_fairness::next_roll_rng;
+use super::error::{AppError, AppResult};
+use super::fear_tracker::{apply_fear_adjustment, emit_fear_update};
+use super::player_character::{
+    apply_hope_adjustment, emit_player_character_update, get_player_character_by_id, PlayerCharacter,
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RollType {
+    #[default]
+    Standard,
+    Duality,
+}
+
+impl RollType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RollType::Standard => "standard",
+            RollType::Duality => "duality",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "duality" => RollType::Duality,
+            _ => RollType::Standard,
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiceRoll {
     pub id: String,
     pub campaign_id: String,
     pub notation: String,
-    pub dice_data: String, // JSON array of dice results
+    pub dice_data: String, // JSON array of dice results, each tagged with its kind (hope, fear, advantage, ...)
     pub modifier: i32,
     pub total: i32,
     pub is_crit: bool,
     pub is_fumble: bool,
     pub shared_with_players: bool,
     pub rolled_at: String,
+    #[serde(default)]
+    pub roll_type: RollType,
+    pub outcome: Option<DualityOutcome>,
+    pub difficulty: Option<i32>,
+    pub player_character_id: Option<String>,
+    /// GM-only roll; never shown to players until revealed
+    #[serde(default)]
+    pub is_secret: bool,
+    /// When a secret roll was first shared with players
+    pub revealed_at: Option<String>,
+    /// Seed session and roll number that produced the dice, for replaying them
+    pub rng_session_id: Option<String>,
+    pub rng_nonce: Option<i64>,
+    /// JSON `RollOptions` the roll was made with
+    pub roll_options: Option<String>,
 }
 
+/// A day or game session of rolls, newest first. `date` is the local day
+/// the group starts on; `started_at`/`ended_at` are local datetimes.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DiceRollsByDate {
     pub date: String,
+    pub started_at: String,
+    pub ended_at: String,
     pub rolls: Vec<DiceRoll>,
 }
 
+/// Optional filters for dice history queries; unset fields match everything
+#[derive(Debug, Default, Deserialize)]
+pub struct DiceRollFilter {
+    /// Case-insensitive substring of the notation, e.g. "d20"
+    pub notation: Option<String>,
+    /// Inclusive start, SQLite datetime in UTC
+    pub from: Option<String>,
+    /// Exclusive end, SQLite datetime in UTC
+    pub to: Option<String>,
+    pub shared: Option<bool>,
+    pub is_crit: Option<bool>,
+    pub is_fumble: Option<bool>,
+    pub player_character_id: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RollGrouping {
+    /// Calendar day in the caller's timezone
+    #[default]
+    Day,
+    /// Runs of rolls without a long break between them
+    Session,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiceRollPage {
+    pub rolls: Vec<DiceRoll>,
+    /// Pass back as `cursor` to fetch the next (older) page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiceRollGroupPage {
+    /// The last group may continue on the next page
+    pub groups: Vec<DiceRollsByDate>,
+    pub next_cursor: Option<String>,
+}
+
+/// Values for a new `dice_rolls` row
+#[derive(Default)]
+pub struct NewDiceRoll<'a> {
+    pub campaign_id: &'a str,
+    pub notation: &'a str,
+    pub dice_data: &'a str,
+    pub modifier: i32,
+    pub total: i32,
+    pub is_crit: bool,
+    pub is_fumble: bool,
+    pub shared_with_players: bool,
+    pub roll_type: RollType,
+    pub outcome: Option<DualityOutcome>,
+    pub difficulty: Option<i32>,
+    pub player_character_id: Option<&'a str>,
+    pub is_secret: bool,
+    pub rng_session_id: Option<&'a str>,
+    pub rng_nonce: Option<i64>,
+    pub roll_options: Option<&'a str>,
+}
+
+/// A roll as the player view renders it (`dice-roll-shared` payload)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDiceRoll {
+    pub id: String,
+    pub campaign_id: String,
+    pub notation: String,
+    pub dice_results: Vec<DieResult>,
+    pub total: i32,
+    pub modifier: i32,
+    pub is_crit: bool,
+    pub is_fumble: bool,
+    pub outcome: Option<DualityOutcome>,
+    pub rolled_at: String,
+}
+
+impl From<&DiceRoll> for SharedDiceRoll {
+    fn from(roll: &DiceRoll) -> Self {
+        SharedDiceRoll {
+            id: roll.id.clone(),
+            campaign_id: roll.campaign_id.clone(),
+            notation: roll.notation.clone(),
+            dice_results: serde_json::from_str(&roll.dice_data).unwrap_or_default(),
+            total: roll.total,
+            modifier: roll.modifier,
+            is_crit: roll.is_crit,
+            is_fumble: roll.is_fumble,
+            outcome: roll.outcome,
+            rolled_at: roll.rolled_at.clone(),
+        }
+    }
+}
+
+/// Payload of `dice-roll-hidden`
+#[derive(Clone, Serialize)]
+pub struct HiddenDiceRoll {
+    pub id: String,
+    pub campaign_id: String,
+}
+
+/// Who is rolling and how the roll is shown, shared by every kind of roll
+#[derive(Default)]
+pub struct RollSettings<'a> {
+    pub campaign_id: &'a str,
+    pub player_character_id: Option<&'a str>,
+    pub options: RollOptions,
+    pub shared_with_players: bool,
+    /// GM-only roll; overrides `shared_with_players`
+    pub secret: bool,
+}
+
+/// Result of a duality roll along with the resources it changed
+#[derive(Clone, Serialize)]
+pub struct DualityRollResult {
+    pub roll: DiceRoll,
+    pub hope: i32,
+    pub fear: i32,
+    /// New campaign fear level, if the roll was with Fear
+    pub fear_level: Option<i32>,
+    /// The rolling character after gaining Hope (and clearing Stress on a crit)
+    pub character: Option<PlayerCharacter>,
+    /// Dynamic countdowns this roll advanced
+    pub tracker_advances: Vec<TrackerAdvance>,
+}
+
 fn row_to_dice_roll(row: &Row) -> rusqlite::Result<DiceRoll> {
     Ok(DiceRoll {
         id: row.get(0)?,
@@ -38,121 +213,603 @@ fn row_to_dice_roll(row: &Row) -> rusqlite::Result<DiceRoll> {
         is_fumble: row.get::<_, i32>(7)? != 0,
         shared_with_players: row.get::<_, i32>(8)? != 0,
         rolled_at: row.get(9)?,
+        roll_type: RollType::from_str(&row.get::<_, String>(10)?),
+        outcome: row
+            .get::<_, Option<String>>(11)?
+            .and_then(|s| DualityOutcome::from_str(&s)),
+        difficulty: row.get(12)?,
+        player_character_id: row.get(13)?,
+        is_secret: row.get::<_, i32>(14)? != 0,
+        revealed_at: row.get(15)?,
+        rng_session_id: row.get(16)?,
+        rng_nonce: row.get(17)?,
+        roll_options: row.get(18)?,
+    })
+}
+
+const SELECT_COLUMNS: &str = "id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players, rolled_at, roll_type, outcome, difficulty, player_character_id, is_secret, revealed_at, rng_session_id, rng_nonce, roll_options";
+
+/// Insert a roll and read it back to get the server-generated timestamp
+pub fn insert_dice_roll(conn: &Connection, new_roll: &NewDiceRoll) -> AppResult<DiceRoll> {
+    let id = Uuid::new_v4().to_string();
+
+    conn.execute(
+        "INSERT INTO dice_rolls (id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players, roll_type, outcome, difficulty, player_character_id, is_secret, rng_session_id, rng_nonce, roll_options)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        params![
+            id,
+            new_roll.campaign_id,
+            new_roll.notation,
+            new_roll.dice_data,
+            new_roll.modifier,
+            new_roll.total,
+            new_roll.is_crit as i32,
+            new_roll.is_fumble as i32,
+            new_roll.shared_with_players as i32,
+            new_roll.roll_type.as_str(),
+            new_roll.outcome.map(|o| o.as_str()),
+            new_roll.difficulty,
+            new_roll.player_character_id,
+            new_roll.is_secret as i32,
+            new_roll.rng_session_id,
+            new_roll.rng_nonce,
+            new_roll.roll_options
+        ],
+    )?;
+
+    get_dice_roll_by_id(conn, &id)
+}
+
+pub fn get_dice_roll_by_id(conn: &Connection, id: &str) -> AppResult<DiceRoll> {
+    conn.query_row(
+        &format!("SELECT {} FROM dice_rolls WHERE id = ?1", SELECT_COLUMNS),
+        params![id],
+        row_to_dice_roll,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Dice roll not found: {}", id)))
+}
+
+/// Broadcast a newly saved roll, and show it in the player view if shared
+pub(super) fn emit_roll_saved(app: &AppHandle, roll: &DiceRoll) {
+    let _ = app.emit("dice-roll-saved", roll);
+    if roll.shared_with_players {
+        let _ = app.emit("dice-roll-shared", SharedDiceRoll::from(roll));
+    }
+}
+
+const DEFAULT_PAGE_SIZE: i32 = 100;
+
+const MAX_PAGE_SIZE: i32 = 500;
+
+/// A break longer than this between two rolls starts a new game session
+const DEFAULT_SESSION_GAP_MINUTES: i64 = 240;
+
+/// `WHERE` clause for `DiceRollFilter`, bound as ?1..?8 by `filter_params`
+const FILTER_CLAUSE: &str = "campaign_id = ?1
+    AND (?2 IS NULL OR notation LIKE '%' || ?2 || '%')
+    AND (?3 IS NULL OR rolled_at >= datetime(?3))
+    AND (?4 IS NULL OR rolled_at < datetime(?4))
+    AND (?5 IS NULL OR shared_with_players = ?5)
+    AND (?6 IS NULL OR is_crit = ?6)
+    AND (?7 IS NULL OR is_fumble = ?7)
+    AND (?8 IS NULL OR player_character_id = ?8)";
+
+fn filter_params<'a>(campaign_id: &'a str, filter: &'a DiceRollFilter) -> Vec<Box<dyn rusqlite::ToSql + 'a>> {
+    vec![
+        Box::new(campaign_id),
+        Box::new(filter.notation.as_deref().map(str::trim).filter(|n| !n.is_empty())),
+        Box::new(filter.from.as_deref()),
+        Box::new(filter.to.as_deref()),
+        Box::new(filter.shared.map(|b| b as i32)),
+        Box::new(filter.is_crit.map(|b| b as i32)),
+        Box::new(filter.is_fumble.map(|b| b as i32)),
+        Box::new(filter.player_character_id.as_deref()),
+    ]
+}
+
+/// All matching rolls for a campaign in chronological order
+pub fn query_dice_rolls(conn: &Connection, campaign_id: &str, filter: &DiceRollFilter) -> AppResult<Vec<DiceRoll>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM dice_rolls WHERE {} ORDER BY rolled_at, id",
+        SELECT_COLUMNS, FILTER_CLAUSE
+    ))?;
+
+    let values = filter_params(campaign_id, filter);
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    let rolls = stmt
+        .query_map(params.as_slice(), row_to_dice_roll)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rolls)
+}
+
+/// A roll with its local time, for grouping
+struct HistoryRow {
+    roll: DiceRoll,
+    local_rolled_at: String,
+    epoch_seconds: i64,
+}
+
+fn encode_cursor(roll: &DiceRoll) -> String {
+    format!("{}|{}", roll.rolled_at, roll.id)
+}
+
+fn decode_cursor(cursor: &str) -> AppResult<(&str, &str)> {
+    cursor
+        .split_once('|')
+        .ok_or_else(|| AppError::Validation(format!("Invalid history cursor: {}", cursor)))
+}
+
+/// One page of matching rolls, newest first, keyed on `(rolled_at, id)` so
+/// rolls saved while paging don't shift later pages
+fn query_history_page(
+    conn: &Connection,
+    campaign_id: &str,
+    filter: &DiceRollFilter,
+    cursor: Option<&str>,
+    limit: Option<i32>,
+    utc_offset_minutes: i32,
+) -> AppResult<(Vec<HistoryRow>, Option<String>)> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let (cursor_at, cursor_id) = match cursor {
+        Some(cursor) => {
+            let (at, id) = decode_cursor(cursor)?;
+            (Some(at), Some(id))
+        }
+        None => (None, None),
+    };
+    let offset = format!("{:+} minutes", utc_offset_minutes);
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, datetime(rolled_at, ?12), CAST(strftime('%s', rolled_at) AS INTEGER)
+         FROM dice_rolls
+         WHERE {}
+           AND (?9 IS NULL OR rolled_at < ?9 OR (rolled_at = ?9 AND id < ?10))
+         ORDER BY rolled_at DESC, id DESC
+         LIMIT ?11",
+        SELECT_COLUMNS, FILTER_CLAUSE
+    ))?;
+
+    let mut values = filter_params(campaign_id, filter);
+    values.push(Box::new(cursor_at));
+    values.push(Box::new(cursor_id));
+    // One extra row tells us whether there is another page
+    values.push(Box::new(limit + 1));
+    values.push(Box::new(offset));
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+
+    let mut rows = stmt
+        .query_map(params.as_slice(), |row| {
+            Ok(HistoryRow {
+                roll: row_to_dice_roll(row)?,
+                local_rolled_at: row.get(19)?,
+                epoch_seconds: row.get(20)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let next_cursor = if rows.len() > limit as usize {
+        rows.truncate(limit as usize);
+        rows.last().map(|row| encode_cursor(&row.roll))
+    } else {
+        None
+    };
+
+    Ok((rows, next_cursor))
+}
+
+/// Group newest-first rows by local day, or into sessions split by breaks
+/// longer than `session_gap_minutes`
+fn group_history(rows: Vec<HistoryRow>, grouping: RollGrouping, session_gap_minutes: i64) -> Vec<DiceRollsByDate> {
+    let mut groups: Vec<DiceRollsByDate> = Vec::new();
+    let mut previous_epoch: Option<i64> = None;
+
+    for row in rows {
+        let day = row.local_rolled_at.split(' ').next().unwrap_or(&row.local_rolled_at).to_string();
+
+        let starts_new_group = match (groups.last(), grouping) {
+            (None, _) => true,
+            (Some(group), RollGrouping::Day) => group.date != day,
+            (Some(_), RollGrouping::Session) => previous_epoch
+                .map(|newer| newer - row.epoch_seconds > session_gap_minutes * 60)
+                .unwrap_or(true),
+        };
+        previous_epoch = Some(row.epoch_seconds);
+
+        if starts_new_group {
+            groups.push(DiceRollsByDate {
+                date: day,
+                started_at: row.local_rolled_at.clone(),
+                ended_at: row.local_rolled_at,
+                rolls: vec![row.roll],
+            });
+        } else if let Some(group) = groups.last_mut() {
+            // Rows arrive newest first, so each one moves the start back
+            group.date = day;
+            group.started_at = row.local_rolled_at;
+            group.rolls.push(row.roll);
+        }
+    }
+
+    groups
+}
+
+/// Roll a notation and persist it inside the caller's transaction, without
+/// emitting. The caller must commit and then emit.
+pub fn record_roll(
+    conn: &Connection,
+    notation: &str,
+    settings: &RollSettings,
+) -> AppResult<(DiceRoll, RollResult)> {
+    if let Some(pc_id) = settings.player_character_id {
+        ensure_character_in_campaign(conn, pc_id, settings.campaign_id)?;
+    }
+
+    let mut seed = next_roll_rng(conn, settings.campaign_id)?;
+    let result = dice_engine::roll_notation(notation, &settings.options, &mut seed.rng)?;
+    let dice_data = serde_json::to_string(&result.dice)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+    let roll_options = serde_json::to_string(&settings.options)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+
+    let roll = insert_dice_roll(
+        conn,
+        &NewDiceRoll {
+            campaign_id: settings.campaign_id,
+            notation: &result.notation,
+            dice_data: &dice_data,
+            modifier: result.modifier,
+            total: result.total,
+            is_crit: result.is_crit,
+            is_fumble: result.is_fumble,
+            shared_with_players: !settings.secret && settings.shared_with_players,
+            player_character_id: settings.player_character_id,
+            is_secret: settings.secret,
+            rng_session_id: seed.session_id.as_deref(),
+            rng_nonce: seed.nonce,
+            roll_options: Some(&roll_options),
+            ..Default::default()
+        },
+    )?;
+
+    Ok((roll, result))
+}
+
+/// Roll a notation and persist it, emitting `dice-roll-saved` (and
+/// `dice-roll-shared` when shared)
+pub fn perform_roll(
+    conn: &Connection,
+    app: &AppHandle,
+    notation: &str,
+    settings: &RollSettings,
+) -> AppResult<DiceRoll> {
+    // Claiming a seed nonce and saving the roll must succeed or fail together
+    let tx = conn.unchecked_transaction()?;
+    let (roll, _) = record_roll(&tx, notation, settings)?;
+    tx.commit()?;
+
+    emit_roll_saved(app, &roll);
+
+    Ok(roll)
+}
+
+/// Roll Duality Dice, persist the roll and apply its Hope/Fear consequences
+/// inside the caller's transaction, without emitting
+pub fn record_duality_roll(
+    conn: &Connection,
+    modifier: i32,
+    difficulty: Option<i32>,
+    settings: &RollSettings,
+) -> AppResult<DualityRollResult> {
+    let campaign_id = settings.campaign_id;
+
+    if let Some(pc_id) = settings.player_character_id {
+        ensure_character_in_campaign(conn, pc_id, campaign_id)?;
+    }
+
+    let mut seed = next_roll_rng(conn, campaign_id)?;
+    let result = dice_engine::roll_duality(modifier, difficulty, &settings.options, &mut seed.rng)?;
+    let dice_data = serde_json::to_string(&result.dice)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+    let roll_options = serde_json::to_string(&settings.options)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+    let notation = dice_engine::duality_notation(modifier);
+
+    let roll = insert_dice_roll(
+        conn,
+        &NewDiceRoll {
+            campaign_id,
+            notation: &notation,
+            dice_data: &dice_data,
+            modifier,
+            total: result.total,
+            is_crit: result.outcome == DualityOutcome::CriticalSuccess,
+            is_fumble: false,
+            shared_with_players: !settings.secret && settings.shared_with_players,
+            roll_type: RollType::Duality,
+            outcome: Some(result.outcome),
+            difficulty,
+            player_character_id: settings.player_character_id,
+            is_secret: settings.secret,
+            rng_session_id: seed.session_id.as_deref(),
+            rng_nonce: seed.nonce,
+            roll_options: Some(&roll_options),
+        },
+    )?;
+
+    let fear_level = if result.outcome.is_with_fear() {
+        Some(apply_fear_adjustment(conn, campaign_id, 1)?)
+    } else {
+        None
+    };
+
+    let character = match (settings.player_character_id, result.outcome.is_with_hope()) {
+        (Some(pc_id), true) => {
+            if result.outcome == DualityOutcome::CriticalSuccess {
+                conn.execute(
+                    "UPDATE player_characters SET stress_current = MAX(0, stress_current - 1) WHERE id = ?1",
+                    params![pc_id],
+                )?;
+            }
+            Some(apply_hope_adjustment(conn, pc_id, 1)?)
+        }
+        _ => None,
+    };
+
+    // Secret rolls advance linked countdowns only once revealed, so a
+    // visible tracker can't give the outcome away
+    let tracker_advances = if settings.secret {
+        Vec::new()
+    } else {
+        advance_linked_trackers(conn, campaign_id, &roll.id, result.outcome)?
+    };
+
+    Ok(DualityRollResult {
+        roll,
+        hope: result.hope,
+        fear: result.fear,
+        fear_level,
+        character,
+        tracker_advances,
     })
 }
 
+/// Emit the Fear, Hope and countdown changes from a committed duality roll
+pub fn emit_duality_consequences(app: &AppHandle, conn: &Connection, result: &DualityRollResult) -> AppResult<()> {
+    if let Some(level) = result.fear_level {
+        emit_fear_update(app, level, &result.roll.campaign_id)?;
+    }
+    if let Some(character) = &result.character {
+        emit_player_character_update(app, character);
+    }
+    emit_tracker_advances(app, conn, &result.roll.campaign_id, &result.tracker_advances)
+}
+
+/// Roll Duality Dice and apply the consequences in one transaction: a result
+/// with Fear gives the GM a Fear, a result with Hope gives the rolling
+/// character a Hope, and a critical success also clears one of their Stress.
+/// Roll-linked dynamic countdowns advance by their amount for the outcome.
+pub fn perform_duality_roll(
+    conn: &Connection,
+    app: &AppHandle,
+    modifier: i32,
+    difficulty: Option<i32>,
+    settings: &RollSettings,
+) -> AppResult<DualityRollResult> {
+    let tx = conn.unchecked_transaction()?;
+    let result = record_duality_roll(&tx, modifier, difficulty, settings)?;
+    tx.commit()?;
+
+    emit_roll_saved(app, &result.roll);
+    emit_duality_consequences(app, conn, &result)?;
+
+    Ok(result)
+}
+
+fn ensure_character_in_campaign(conn: &Connection, player_character_id: &str, campaign_id: &str) -> AppResult<()> {
+    let character = get_player_character_by_id(conn, player_character_id)?;
+    if character.campaign_id != campaign_id {
+        return Err(AppError::Validation(
+            "Player character belongs to a different campaign".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parse and roll dice notation on the backend, then persist the result.
+/// Every window sees the same authoritative roll via `dice-roll-saved`.
+/// `options` adds advantage/disadvantage d6s and bonus dice on top.
+/// A `secret` roll stays hidden from players until revealed with
+/// `set_dice_roll_shared`.
 #[tauri::command]
-pub fn save_dice_roll(
+pub fn roll_dice(
     db: State<Database>,
     app: AppHandle,
     campaign_id: String,
     notation: String,
-    dice_data: String,
-    modifier: i32,
-    total: i32,
-    is_crit: bool,
-    is_fumble: bool,
-    shared_with_players: bool,
+    options: Option<RollOptions>,
+    shared_with_players: Option<bool>,
+    secret: Option<bool>,
 ) -> AppResult<DiceRoll> {
     db.with_conn(|conn| {
-        let id = Uuid::new_v4().to_string();
-
-        conn.execute(
-            "INSERT INTO dice_rolls (id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
-                id,
-                campaign_id,
-                notation,
-                dice_data,
-                modifier,
-                total,
-                is_crit as i32,
-                is_fumble as i32,
-                shared_with_players as i32
-            ],
-        )?;
+        perform_roll(
+            conn,
+            &app,
+            &notation,
+            &RollSettings {
+                campaign_id: &campaign_id,
+                player_character_id: None,
+                options: options.unwrap_or_default(),
+                shared_with_players: shared_with_players.unwrap_or(false),
+                secret: secret.unwrap_or(false),
+            },
+        )
+    })
+}
 
-        // Fetch the created roll to get the server-generated timestamp
-        let roll = conn.query_row(
-            "SELECT id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players, rolled_at
-             FROM dice_rolls WHERE id = ?1",
-            params![id],
-            row_to_dice_roll,
-        )?;
+/// Roll Daggerheart Duality Dice (a Hope d12 and a Fear d12, plus any
+/// advantage/disadvantage and bonus dice) for an optional player character.
+/// See `perform_duality_roll` for the Hope/Fear consequences.
+#[tauri::command]
+pub fn roll_duality(
+    db: State<Database>,
+    app: AppHandle,
+    campaign_id: String,
+    modifier: Option<i32>,
+    difficulty: Option<i32>,
+    player_character_id: Option<String>,
+    options: Option<RollOptions>,
+    shared_with_players: Option<bool>,
+    secret: Option<bool>,
+) -> AppResult<DualityRollResult> {
+    db.with_conn(|conn| {
+        perform_duality_roll(
+            conn,
+            &app,
+            modifier.unwrap_or(0),
+            difficulty,
+            &RollSettings {
+                campaign_id: &campaign_id,
+                player_character_id: player_character_id.as_deref(),
+                options: options.unwrap_or_default(),
+                shared_with_players: shared_with_players.unwrap_or(false),
+                secret: secret.unwrap_or(false),
+            },
+        )
+    })
+}
 
-        // Emit event for other windows
-        let _ = app.emit("dice-roll-saved", &roll);
+/// Matching rolls, newest first, one page at a time
+#[tauri::command]
+pub fn get_dice_rolls(
+    db: State<Database>,
+    campaign_id: String,
+    limit: Option<i32>,
+    cursor: Option<String>,
+    filter: Option<DiceRollFilter>,
+) -> AppResult<DiceRollPage> {
+    db.with_conn(|conn| {
+        let filter = filter.unwrap_or_default();
+        let (rows, next_cursor) = query_history_page(conn, &campaign_id, &filter, cursor.as_deref(), limit, 0)?;
 
-        Ok(roll)
+        Ok(DiceRollPage {
+            rolls: rows.into_iter().map(|row| row.roll).collect(),
+            next_cursor,
+        })
     })
 }
 
+/// Matching rolls grouped by day or game session, newest first, one page at
+/// a time. `utc_offset_minutes` is the caller's offset from UTC (e.g. -300 for
+/// US Eastern) so days split at local midnight; sessions are split by breaks
+/// longer than `session_gap_minutes` (4 hours by default).
 #[tauri::command]
-pub fn get_dice_rolls(
+pub fn get_dice_rolls_by_date(
     db: State<Database>,
     campaign_id: String,
     limit: Option<i32>,
-) -> AppResult<Vec<DiceRoll>> {
+    cursor: Option<String>,
+    filter: Option<DiceRollFilter>,
+    group_by: Option<RollGrouping>,
+    utc_offset_minutes: Option<i32>,
+    session_gap_minutes: Option<i64>,
+) -> AppResult<DiceRollGroupPage> {
     db.with_conn(|conn| {
-        let limit = limit.unwrap_or(100);
+        let filter = filter.unwrap_or_default();
+        let (rows, next_cursor) = query_history_page(
+            conn,
+            &campaign_id,
+            &filter,
+            cursor.as_deref(),
+            limit,
+            utc_offset_minutes.unwrap_or(0),
+        )?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players, rolled_at
-             FROM dice_rolls
-             WHERE campaign_id = ?1
-             ORDER BY rolled_at DESC
-             LIMIT ?2",
+        Ok(DiceRollGroupPage {
+            groups: group_history(
+                rows,
+                group_by.unwrap_or_default(),
+                session_gap_minutes.unwrap_or(DEFAULT_SESSION_GAP_MINUTES).max(1),
+            ),
+            next_cursor,
+        })
+    })
+}
+
+/// Share an existing roll with the player view or take it back down.
+/// Sharing a secret roll reveals it; the first reveal time is kept, and a
+/// secret duality roll advances its linked countdowns then.
+#[tauri::command]
+pub fn set_dice_roll_shared(
+    db: State<Database>,
+    app: AppHandle,
+    id: String,
+    shared: bool,
+) -> AppResult<DiceRoll> {
+    db.with_conn(|conn| {
+        let existing = get_dice_roll_by_id(conn, &id)?;
+        let first_reveal = shared && existing.is_secret && existing.revealed_at.is_none();
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE dice_rolls
+             SET shared_with_players = ?2,
+                 revealed_at = CASE
+                     WHEN ?2 = 1 AND is_secret = 1 AND revealed_at IS NULL THEN datetime('now')
+                     ELSE revealed_at
+                 END
+             WHERE id = ?1",
+            params![id, shared as i32],
         )?;
+        let tracker_advances = match existing.outcome {
+            Some(outcome) if first_reveal => advance_linked_trackers(&tx, &existing.campaign_id, &id, outcome)?,
+            _ => Vec::new(),
+        };
+        tx.commit()?;
 
-        let rolls = stmt
-            .query_map(params![campaign_id, limit], row_to_dice_roll)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let roll = get_dice_roll_by_id(conn, &id)?;
 
-        Ok(rolls)
+        let _ = app.emit("dice-roll-saved", &roll);
+        if shared {
+            let _ = app.emit("dice-roll-shared", SharedDiceRoll::from(&roll));
+        } else {
+            let _ = app.emit(
+                "dice-roll-hidden",
+                HiddenDiceRoll {
+                    id: roll.id.clone(),
+                    campaign_id: roll.campaign_id.clone(),
+                },
+            );
+        }
+        emit_tracker_advances(&app, conn, &roll.campaign_id, &tracker_advances)?;
+
+        Ok(roll)
     })
 }
 
+/// Rolls currently shared with players, most recent first, for the player view
 #[tauri::command]
-pub fn get_dice_rolls_by_date(
+pub fn get_shared_dice_rolls(
     db: State<Database>,
     campaign_id: String,
     limit: Option<i32>,
-) -> AppResult<Vec<DiceRollsByDate>> {
+) -> AppResult<Vec<SharedDiceRoll>> {
     db.with_conn(|conn| {
-        let limit = limit.unwrap_or(100);
+        let limit = limit.unwrap_or(20);
 
-        let mut stmt = conn.prepare(
-            "SELECT id, campaign_id, notation, dice_data, modifier, total, is_crit, is_fumble, shared_with_players, rolled_at
-             FROM dice_rolls
-             WHERE campaign_id = ?1
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM dice_rolls
+             WHERE campaign_id = ?1 AND shared_with_players = 1
              ORDER BY rolled_at DESC
              LIMIT ?2",
-        )?;
+            SELECT_COLUMNS
+        ))?;
 
-        let rolls: Vec<DiceRoll> = stmt
+        let rolls = stmt
             .query_map(params![campaign_id, limit], row_to_dice_roll)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Group rolls by date
-        let mut grouped: std::collections::HashMap<String, Vec<DiceRoll>> = std::collections::HashMap::new();
-
-        for roll in rolls {
-            // Extract date portion (YYYY-MM-DD) from rolled_at
-            let date = roll.rolled_at.split(' ').next().unwrap_or(&roll.rolled_at).to_string();
-            grouped.entry(date).or_default().push(roll);
-        }
-
-        // Convert to sorted vector
-        let mut result: Vec<DiceRollsByDate> = grouped
-            .into_iter()
-            .map(|(date, rolls)| DiceRollsByDate { date, rolls })
-            .collect();
-
-        // Sort by date descending (most recent first)
-        result.sort_by(|a, b| b.date.cmp(&a.date));
-
-        Ok(result)
+        Ok(rolls.iter().map(SharedDiceRoll::from).collect())
     })
 }
 
@@ -0,0 +1,1069 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use super::error::{AppError, AppResult};
+
+// ============================================================================
+// Limits
+// ============================================================================
+
+/// Maximum number of dice in a single group (e.g. the `100` in `100d6`)
+pub const MAX_DICE_COUNT: i32 = 100;
+
+/// Maximum number of sides on a single die
+pub const MAX_DIE_SIDES: i32 = 1000;
+
+/// Maximum number of dice groups in one expression
+const MAX_DICE_GROUPS: usize = 20;
+
+/// Cap on explosions and rerolls per die so a roll always terminates
+pub(crate) const MAX_CHAIN: usize = 100;
+
+const MAX_NOTATION_LENGTH: usize = 200;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A comparison against a single die face, e.g. the `>5` in `3d6!>5`.
+/// Like most VTTs, `>N` and `<N` are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compare {
+    Equal(i32),
+    AtLeast(i32),
+    AtMost(i32),
+}
+
+impl Compare {
+    pub fn matches(&self, value: i32) -> bool {
+        match *self {
+            Compare::Equal(n) => value == n,
+            Compare::AtLeast(n) => value >= n,
+            Compare::AtMost(n) => value <= n,
+        }
+    }
+
+    /// Number of faces on a die with `sides` sides that match
+    pub(crate) fn face_count(&self, sides: i32) -> i32 {
+        (1..=sides).filter(|face| self.matches(*face)).count() as i32
+    }
+}
+
+impl fmt::Display for Compare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compare::Equal(n) => write!(f, "{}", n),
+            Compare::AtLeast(n) => write!(f, ">{}", n),
+            Compare::AtMost(n) => write!(f, "<{}", n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeepDrop {
+    KeepHighest(i32),
+    KeepLowest(i32),
+    DropHighest(i32),
+    DropLowest(i32),
+}
+
+impl fmt::Display for KeepDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeepDrop::KeepHighest(n) => write!(f, "kh{}", n),
+            KeepDrop::KeepLowest(n) => write!(f, "kl{}", n),
+            KeepDrop::DropHighest(n) => write!(f, "dh{}", n),
+            KeepDrop::DropLowest(n) => write!(f, "dl{}", n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reroll {
+    pub compare: Compare,
+    /// Reroll at most once (`ro`) instead of until the face no longer matches (`r`)
+    pub once: bool,
+}
+
+/// A single group of identical dice with its modifiers, e.g. `4d6kh3` or `2d10!`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiceTerm {
+    pub count: i32,
+    pub sides: i32,
+    pub explode: Option<Compare>,
+    pub reroll: Option<Reroll>,
+    pub keep: Option<KeepDrop>,
+}
+
+impl fmt::Display for DiceTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        if let Some(reroll) = &self.reroll {
+            write!(f, "{}{}", if reroll.once { "ro" } else { "r" }, reroll.compare)?;
+        }
+        if let Some(explode) = &self.explode {
+            if *explode == Compare::Equal(self.sides) {
+                write!(f, "!")?;
+            } else {
+                write!(f, "!{}", explode)?;
+            }
+        }
+        if let Some(keep) = &self.keep {
+            write!(f, "{}", keep)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Parsed dice expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(i32),
+    Dice(DiceTerm),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+/// What role a die played in a roll
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DieKind {
+    #[default]
+    Standard,
+    Hope,
+    Fear,
+    /// Added to the total
+    Advantage,
+    /// Subtracted from the total
+    Disadvantage,
+    /// Extra die added to the total, e.g. a Rally die
+    Bonus,
+}
+
+/// A single physical die in a roll result
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct DieResult {
+    pub sides: i32,
+    pub result: i32,
+    /// Index into `RollResult::groups`
+    #[serde(default)]
+    pub group: usize,
+    #[serde(default)]
+    pub kind: DieKind,
+    /// Source of a bonus die, e.g. "Rally"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Excluded from the total by a keep/drop modifier
+    #[serde(default)]
+    pub dropped: bool,
+    /// Added to the pool because the previous die exploded
+    #[serde(default)]
+    pub exploded: bool,
+    /// Value came from a reroll
+    #[serde(default)]
+    pub rerolled: bool,
+}
+
+impl DieResult {
+    pub fn new(sides: i32, result: i32, group: usize, kind: DieKind) -> Self {
+        Self {
+            sides,
+            result,
+            group,
+            kind,
+            label: None,
+            dropped: false,
+            exploded: false,
+            rerolled: false,
+        }
+    }
+
+    /// Signed contribution of this die to the total
+    pub fn value(&self) -> i32 {
+        match (self.dropped, self.kind) {
+            (true, _) => 0,
+            (false, DieKind::Disadvantage) => -self.result,
+            (false, _) => self.result,
+        }
+    }
+}
+
+/// An extra die added to a roll, e.g. a Rally die or an Experience spend
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BonusDie {
+    pub sides: i32,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Advantage, disadvantage and bonus dice layered on top of a roll
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct RollOptions {
+    /// Sources of advantage; each cancels one source of disadvantage
+    #[serde(default)]
+    pub advantage: u32,
+    #[serde(default)]
+    pub disadvantage: u32,
+    #[serde(default)]
+    pub bonus_dice: Vec<BonusDie>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DiceGroupResult {
+    pub notation: String,
+    pub sides: i32,
+    pub subtotal: i32,
+}
+
+/// Fully evaluated roll of a dice expression
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RollResult {
+    pub notation: String,
+    pub groups: Vec<DiceGroupResult>,
+    pub dice: Vec<DieResult>,
+    /// Everything in the total that didn't come from a kept die
+    pub modifier: i32,
+    pub total: i32,
+    pub is_crit: bool,
+    pub is_fumble: bool,
+}
+
+/// Outcome of a Daggerheart duality roll
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DualityOutcome {
+    /// Hope and Fear dice match: always a success, with Hope
+    CriticalSuccess,
+    SuccessWithHope,
+    SuccessWithFear,
+    FailureWithHope,
+    FailureWithFear,
+    /// No difficulty was given, so only the Hope/Fear side is known
+    WithHope,
+    WithFear,
+}
+
+impl DualityOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DualityOutcome::CriticalSuccess => "critical_success",
+            DualityOutcome::SuccessWithHope => "success_with_hope",
+            DualityOutcome::SuccessWithFear => "success_with_fear",
+            DualityOutcome::FailureWithHope => "failure_with_hope",
+            DualityOutcome::FailureWithFear => "failure_with_fear",
+            DualityOutcome::WithHope => "with_hope",
+            DualityOutcome::WithFear => "with_fear",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "critical_success" => Some(DualityOutcome::CriticalSuccess),
+            "success_with_hope" => Some(DualityOutcome::SuccessWithHope),
+            "success_with_fear" => Some(DualityOutcome::SuccessWithFear),
+            "failure_with_hope" => Some(DualityOutcome::FailureWithHope),
+            "failure_with_fear" => Some(DualityOutcome::FailureWithFear),
+            "with_hope" => Some(DualityOutcome::WithHope),
+            "with_fear" => Some(DualityOutcome::WithFear),
+            _ => None,
+        }
+    }
+
+    /// The player gains Hope (criticals count as with Hope)
+    pub fn is_with_hope(&self) -> bool {
+        matches!(
+            self,
+            DualityOutcome::CriticalSuccess
+                | DualityOutcome::SuccessWithHope
+                | DualityOutcome::FailureWithHope
+                | DualityOutcome::WithHope
+        )
+    }
+
+    /// The GM gains Fear
+    pub fn is_with_fear(&self) -> bool {
+        !self.is_with_hope()
+    }
+
+    /// `None` when no difficulty was given
+    pub fn is_success(&self) -> Option<bool> {
+        match self {
+            DualityOutcome::CriticalSuccess
+            | DualityOutcome::SuccessWithHope
+            | DualityOutcome::SuccessWithFear => Some(true),
+            DualityOutcome::FailureWithHope | DualityOutcome::FailureWithFear => Some(false),
+            DualityOutcome::WithHope | DualityOutcome::WithFear => None,
+        }
+    }
+}
+
+/// Result of rolling a Hope d12 and a Fear d12
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DualityResult {
+    pub hope: i32,
+    pub fear: i32,
+    /// Hope and Fear dice followed by any advantage/disadvantage and bonus dice
+    pub dice: Vec<DieResult>,
+    pub modifier: i32,
+    pub total: i32,
+    pub difficulty: Option<i32>,
+    pub outcome: DualityOutcome,
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+/// Parse dice notation such as `2d6+3`, `4d6kh3`, `2d20kl1+5`, `3d6!`,
+/// `1d20ro1` or `(1d8+2)*2`.
+///
+/// Supported per-group modifiers:
+/// - `kh N` / `kl N` / `k N`: keep highest/lowest N (`k` = `kh`, N defaults to 1)
+/// - `dh N` / `dl N`: drop highest/lowest N (N defaults to 1)
+/// - `!` / `!N` / `!>N` / `!<N`: explode on max, on N, on N or more, on N or less
+/// - `r N` / `ro N`: reroll matching faces (repeatedly / once), N defaults to 1
+pub fn parse_notation(notation: &str) -> AppResult<Expr> {
+    if notation.len() > MAX_NOTATION_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Dice notation is too long (max {} characters)",
+            MAX_NOTATION_LENGTH
+        )));
+    }
+
+    let chars: Vec<char> = notation
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    if chars.is_empty() {
+        return Err(AppError::Validation("Dice notation is empty".to_string()));
+    }
+
+    let mut parser = Parser { chars, pos: 0, groups: 0 };
+    let expr = parser.parse_expr()?;
+
+    if let Some(c) = parser.peek() {
+        return Err(parser.error(&format!("Unexpected '{}'", c)));
+    }
+
+    Ok(expr)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> AppError {
+        AppError::Validation(format!("Invalid dice notation at position {}: {}", self.pos + 1, message))
+    }
+
+    fn parse_expr(&mut self) -> AppResult<Expr> {
+        let mut left = self.parse_term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => BinaryOp::Add,
+                Some('-') => BinaryOp::Subtract,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_term()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_term(&mut self) -> AppResult<Expr> {
+        let mut left = self.parse_factor()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') | Some('x') => BinaryOp::Multiply,
+                Some('/') => BinaryOp::Divide,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_factor()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_factor(&mut self) -> AppResult<Expr> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Expr::Negate(Box::new(self.parse_factor()?)))
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.parse_factor()
+            }
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.parse_expr()?;
+                if !self.eat(')') {
+                    return Err(self.error("Expected ')'"));
+                }
+                Ok(inner)
+            }
+            Some('d') => self.parse_dice(1),
+            Some(c) if c.is_ascii_digit() => {
+                let value = self.parse_number()?;
+                if self.peek() == Some('d') {
+                    self.parse_dice(value)
+                } else {
+                    Ok(Expr::Number(value))
+                }
+            }
+            Some(c) => Err(self.error(&format!("Unexpected '{}'", c))),
+            None => Err(self.error("Unexpected end of notation")),
+        }
+    }
+
+    fn parse_number(&mut self) -> AppResult<i32> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("Expected a number"));
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse::<i32>()
+            .map_err(|_| self.error("Number is too large"))
+    }
+
+    fn parse_optional_number(&mut self, default: i32) -> AppResult<i32> {
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() => self.parse_number(),
+            _ => Ok(default),
+        }
+    }
+
+    fn parse_compare(&mut self, default: Option<Compare>) -> AppResult<Compare> {
+        if self.eat('>') {
+            return Ok(Compare::AtLeast(self.parse_number()?));
+        }
+        if self.eat('<') {
+            return Ok(Compare::AtMost(self.parse_number()?));
+        }
+        if self.eat('=') {
+            return Ok(Compare::Equal(self.parse_number()?));
+        }
+        match (self.peek(), default) {
+            (Some(c), _) if c.is_ascii_digit() => Ok(Compare::Equal(self.parse_number()?)),
+            (_, Some(default)) => Ok(default),
+            _ => Err(self.error("Expected a comparison")),
+        }
+    }
+
+    fn parse_dice(&mut self, count: i32) -> AppResult<Expr> {
+        // Consume the 'd'
+        self.pos += 1;
+
+        let sides = if self.eat('%') { 100 } else { self.parse_number()? };
+
+        if !(1..=MAX_DICE_COUNT).contains(&count) {
+            return Err(AppError::Validation(format!(
+                "Dice count must be between 1 and {}",
+                MAX_DICE_COUNT
+            )));
+        }
+        if !(2..=MAX_DIE_SIDES).contains(&sides) {
+            return Err(AppError::Validation(format!(
+                "Dice must have between 2 and {} sides",
+                MAX_DIE_SIDES
+            )));
+        }
+
+        self.groups += 1;
+        if self.groups > MAX_DICE_GROUPS {
+            return Err(AppError::Validation(format!(
+                "Too many dice groups (max {})",
+                MAX_DICE_GROUPS
+            )));
+        }
+
+        let mut term = DiceTerm {
+            count,
+            sides,
+            explode: None,
+            reroll: None,
+            keep: None,
+        };
+
+        loop {
+            match (self.peek(), self.chars.get(self.pos + 1).copied()) {
+                (Some('!'), _) => {
+                    self.pos += 1;
+                    if term.explode.is_some() {
+                        return Err(self.error("Dice can only explode once"));
+                    }
+                    term.explode = Some(self.parse_compare(Some(Compare::Equal(sides)))?);
+                }
+                (Some('r'), _) => {
+                    self.pos += 1;
+                    if term.reroll.is_some() {
+                        return Err(self.error("Only one reroll modifier is allowed"));
+                    }
+                    let once = self.eat('o');
+                    let compare = self.parse_compare(Some(Compare::Equal(1)))?;
+                    term.reroll = Some(Reroll { compare, once });
+                }
+                (Some('k'), next) => {
+                    self.pos += 1;
+                    let keep_lowest = next == Some('l');
+                    if keep_lowest || next == Some('h') {
+                        self.pos += 1;
+                    }
+                    let n = self.parse_optional_number(1)?;
+                    let keep = if keep_lowest { KeepDrop::KeepLowest(n) } else { KeepDrop::KeepHighest(n) };
+                    self.set_keep(&mut term, keep)?;
+                }
+                (Some('d'), Some(which @ ('h' | 'l'))) => {
+                    self.pos += 2;
+                    let n = self.parse_optional_number(1)?;
+                    let keep = if which == 'h' { KeepDrop::DropHighest(n) } else { KeepDrop::DropLowest(n) };
+                    self.set_keep(&mut term, keep)?;
+                }
+                _ => break,
+            }
+        }
+
+        if let Some(explode) = &term.explode {
+            if explode.face_count(sides) >= sides {
+                return Err(AppError::Validation(format!(
+                    "{}: exploding on every face would never stop",
+                    term
+                )));
+            }
+        }
+        if let Some(reroll) = &term.reroll {
+            if !reroll.once && reroll.compare.face_count(sides) >= sides {
+                return Err(AppError::Validation(format!(
+                    "{}: rerolling every face would never stop",
+                    term
+                )));
+            }
+        }
+
+        Ok(Expr::Dice(term))
+    }
+
+    fn set_keep(&self, term: &mut DiceTerm, keep: KeepDrop) -> AppResult<()> {
+        if term.keep.is_some() {
+            return Err(self.error("Only one keep/drop modifier is allowed"));
+        }
+        term.keep = Some(keep);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+/// Parse and roll a dice expression
+pub fn roll_notation<R: Rng + ?Sized>(
+    notation: &str,
+    options: &RollOptions,
+    rng: &mut R,
+) -> AppResult<RollResult> {
+    let expr = parse_notation(notation)?;
+    roll_expr(notation.trim(), &expr, options, rng)
+}
+
+/// Roll an already-parsed expression
+pub fn roll_expr<R: Rng + ?Sized>(
+    notation: &str,
+    expr: &Expr,
+    options: &RollOptions,
+    rng: &mut R,
+) -> AppResult<RollResult> {
+    validate_options(options)?;
+
+    let mut groups = Vec::new();
+    let mut dice = Vec::new();
+
+    let overflow = || AppError::OutOfRange("Roll total is too large".to_string());
+
+    let total = evaluate(expr, rng, &mut groups, &mut dice)?;
+    let total = i32::try_from(total).map_err(|_| overflow())?;
+
+    let kept: Vec<&DieResult> = dice.iter().filter(|d| !d.dropped).collect();
+    let dice_total: i32 = kept.iter().map(|d| d.result).sum();
+
+    // Matches the dice bag: crits and fumbles only apply to a lone d20
+    let lone_d20 = kept.len() == 1 && kept[0].sides == 20;
+    let is_crit = lone_d20 && kept[0].result == 20;
+    let is_fumble = lone_d20 && kept[0].result == 1;
+
+    let extra = roll_option_dice(options, groups.len(), rng);
+    for die in &extra {
+        groups.push(DiceGroupResult {
+            notation: format!("1d{}", die.sides),
+            sides: die.sides,
+            subtotal: die.value(),
+        });
+    }
+    let extra_total: i32 = extra.iter().map(|d| d.value()).sum();
+    dice.extend(extra);
+
+    Ok(RollResult {
+        notation: notation.to_string(),
+        groups,
+        dice,
+        modifier: total.checked_sub(dice_total).ok_or_else(overflow)?,
+        total: total.checked_add(extra_total).ok_or_else(overflow)?,
+        is_crit,
+        is_fumble,
+    })
+}
+
+/// Sides on advantage and disadvantage dice
+pub const ADVANTAGE_DIE_SIDES: i32 = 6;
+
+pub(crate) fn validate_options(options: &RollOptions) -> AppResult<()> {
+    if options.bonus_dice.len() > MAX_DICE_COUNT as usize {
+        return Err(AppError::Validation(format!(
+            "Too many bonus dice (max {})",
+            MAX_DICE_COUNT
+        )));
+    }
+    for bonus in &options.bonus_dice {
+        if !(2..=MAX_DIE_SIDES).contains(&bonus.sides) {
+            return Err(AppError::Validation(format!(
+                "Dice must have between 2 and {} sides",
+                MAX_DIE_SIDES
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Roll the advantage/disadvantage die and bonus dice for a roll. Advantage
+/// and disadvantage cancel one-for-one; whatever is left rolls a single d6.
+/// Group indices start at `first_group`.
+fn roll_option_dice<R: Rng + ?Sized>(options: &RollOptions, first_group: usize, rng: &mut R) -> Vec<DieResult> {
+    let mut dice = Vec::new();
+    let mut group = first_group;
+
+    let net = options.advantage as i64 - options.disadvantage as i64;
+    if net != 0 {
+        let kind = if net > 0 { DieKind::Advantage } else { DieKind::Disadvantage };
+        dice.push(DieResult::new(
+            ADVANTAGE_DIE_SIDES,
+            rng.gen_range(1..=ADVANTAGE_DIE_SIDES),
+            group,
+            kind,
+        ));
+        group += 1;
+    }
+
+    for bonus in &options.bonus_dice {
+        let mut die = DieResult::new(bonus.sides, rng.gen_range(1..=bonus.sides), group, DieKind::Bonus);
+        die.label = bonus.label.clone();
+        dice.push(die);
+        group += 1;
+    }
+
+    dice
+}
+
+fn evaluate<R: Rng + ?Sized>(
+    expr: &Expr,
+    rng: &mut R,
+    groups: &mut Vec<DiceGroupResult>,
+    dice: &mut Vec<DieResult>,
+) -> AppResult<i64> {
+    let overflow = || AppError::OutOfRange("Roll total is too large".to_string());
+
+    match expr {
+        Expr::Number(n) => Ok(*n as i64),
+        Expr::Dice(term) => {
+            let group = groups.len();
+            let rolled = roll_term(term, group, rng);
+            let subtotal: i32 = rolled.iter().filter(|d| !d.dropped).map(|d| d.result).sum();
+
+            groups.push(DiceGroupResult {
+                notation: term.to_string(),
+                sides: term.sides,
+                subtotal,
+            });
+            dice.extend(rolled);
+
+            Ok(subtotal as i64)
+        }
+        Expr::Negate(inner) => evaluate(inner, rng, groups, dice)?.checked_neg().ok_or_else(overflow),
+        Expr::Binary(op, left, right) => {
+            let left = evaluate(left, rng, groups, dice)?;
+            let right = evaluate(right, rng, groups, dice)?;
+            match op {
+                BinaryOp::Add => left.checked_add(right).ok_or_else(overflow),
+                BinaryOp::Subtract => left.checked_sub(right).ok_or_else(overflow),
+                BinaryOp::Multiply => left.checked_mul(right).ok_or_else(overflow),
+                BinaryOp::Divide => {
+                    if right == 0 {
+                        return Err(AppError::InvalidOperation("Division by zero".to_string()));
+                    }
+                    // Round down, like most tabletop rules
+                    let quotient = left.checked_div(right).ok_or_else(overflow)?;
+                    if left % right != 0 && (left < 0) != (right < 0) {
+                        Ok(quotient - 1)
+                    } else {
+                        Ok(quotient)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Roll one die, applying the term's reroll rule
+fn roll_die<R: Rng + ?Sized>(term: &DiceTerm, group: usize, rng: &mut R) -> DieResult {
+    let mut die = DieResult::new(term.sides, rng.gen_range(1..=term.sides), group, DieKind::Standard);
+
+    if let Some(reroll) = &term.reroll {
+        let mut attempts = 0;
+        while reroll.compare.matches(die.result) && attempts < MAX_CHAIN {
+            die.result = rng.gen_range(1..=term.sides);
+            die.rerolled = true;
+            attempts += 1;
+            if reroll.once {
+                break;
+            }
+        }
+    }
+
+    die
+}
+
+fn roll_term<R: Rng + ?Sized>(term: &DiceTerm, group: usize, rng: &mut R) -> Vec<DieResult> {
+    let mut dice = Vec::with_capacity(term.count as usize);
+
+    for _ in 0..term.count {
+        let mut die = roll_die(term, group, rng);
+        let mut chain = 0;
+
+        while let Some(explode) = &term.explode {
+            if !explode.matches(die.result) || chain >= MAX_CHAIN {
+                break;
+            }
+            dice.push(die);
+            die = roll_die(term, group, rng);
+            die.exploded = true;
+            chain += 1;
+        }
+
+        dice.push(die);
+    }
+
+    if let Some(keep) = term.keep {
+        apply_keep(&mut dice, keep);
+    }
+
+    dice
+}
+
+fn apply_keep(dice: &mut [DieResult], keep: KeepDrop) {
+    let len = dice.len();
+
+    // Indices sorted from lowest to highest result (stable, so ties drop left to right)
+    let mut order: Vec<usize> = (0..len).collect();
+    order.sort_by_key(|&i| dice[i].result);
+
+    let clamp = |n: i32| (n.max(0) as usize).min(len);
+    let to_drop: Vec<usize> = match keep {
+        KeepDrop::KeepHighest(n) => order[..len - clamp(n)].to_vec(),
+        KeepDrop::KeepLowest(n) => order[clamp(n)..].to_vec(),
+        KeepDrop::DropHighest(n) => order[len - clamp(n)..].to_vec(),
+        KeepDrop::DropLowest(n) => order[..clamp(n)].to_vec(),
+    };
+
+    for i in to_drop {
+        dice[i].dropped = true;
+    }
+}
+
+/// Expected total of an expression, when it can be computed in closed form.
+/// Returns `None` for keep/drop, exploding and reroll modifiers and division.
+pub fn expected_value(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Number(n) => Some(*n as f64),
+        Expr::Dice(term) => {
+            if term.explode.is_some() || term.reroll.is_some() || term.keep.is_some() {
+                return None;
+            }
+            Some(term.count as f64 * (term.sides as f64 + 1.0) / 2.0)
+        }
+        Expr::Negate(inner) => expected_value(inner).map(|v| -v),
+        Expr::Binary(op, left, right) => match op {
+            BinaryOp::Add => Some(expected_value(left)? + expected_value(right)?),
+            BinaryOp::Subtract => Some(expected_value(left)? - expected_value(right)?),
+            // Only linear when one side is a constant
+            BinaryOp::Multiply => match (left.as_ref(), right.as_ref()) {
+                (Expr::Number(n), other) | (other, Expr::Number(n)) => {
+                    Some(*n as f64 * expected_value(other)?)
+                }
+                _ => None,
+            },
+            BinaryOp::Divide => None,
+        },
+    }
+}
+
+/// Expected contribution of an advantage, disadvantage or bonus die
+pub fn expected_die_value(die: &DieResult) -> f64 {
+    let mean = (die.sides as f64 + 1.0) / 2.0;
+    match die.kind {
+        DieKind::Disadvantage => -mean,
+        _ => mean,
+    }
+}
+
+// ============================================================================
+// Variables
+// ============================================================================
+
+/// Values for `@name` variables in saved roll notation. Names are matched
+/// case-insensitively, ignoring spaces, `_` and `-`, so `@exp:silver_tongue`
+/// finds the Experience "Silver Tongue".
+#[derive(Clone, Debug, Default)]
+pub struct RollVariables {
+    values: HashMap<String, i32>,
+}
+
+impl RollVariables {
+    pub fn set(&mut self, name: &str, value: i32) {
+        self.values.insert(variable_key(name), value);
+    }
+
+    pub fn set_experience(&mut self, name: &str, value: i32) {
+        self.values.insert(variable_key(&format!("exp:{}", name)), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<i32> {
+        self.values.get(&variable_key(name)).copied()
+    }
+}
+
+fn variable_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Names of the variables referenced by a notation, in order of appearance
+pub fn notation_variables(notation: &str) -> AppResult<Vec<String>> {
+    let mut names = Vec::new();
+    substitute_variables_with(notation, |name| {
+        names.push(name.to_string());
+        Some(0)
+    })?;
+    Ok(names)
+}
+
+/// Replace every `@name` / `@exp:Name` (or braced `@{name}`, needed before a
+/// `d` as in `@{proficiency}d8`) with its value, ready for `parse_notation`.
+pub fn substitute_variables(notation: &str, variables: &RollVariables) -> AppResult<String> {
+    substitute_variables_with(notation, |name| variables.get(name))
+}
+
+fn substitute_variables_with<F>(notation: &str, mut resolve: F) -> AppResult<String>
+where
+    F: FnMut(&str) -> Option<i32>,
+{
+    let chars: Vec<char> = notation.chars().collect();
+    let mut output = String::with_capacity(notation.len());
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        if chars[pos] != '@' {
+            output.push(chars[pos]);
+            pos += 1;
+            continue;
+        }
+        pos += 1;
+
+        let name: String = if chars.get(pos) == Some(&'{') {
+            let end = chars[pos..]
+                .iter()
+                .position(|&c| c == '}')
+                .map(|offset| pos + offset)
+                .ok_or_else(|| AppError::Validation("Unclosed '{' in roll variable".to_string()))?;
+            let name = chars[pos + 1..end].iter().collect();
+            pos = end + 1;
+            name
+        } else {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_' || chars[pos] == ':') {
+                pos += 1;
+            }
+            chars[start..pos].iter().collect()
+        };
+
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Roll variable is missing a name after '@'".to_string()));
+        }
+
+        let value = resolve(name.trim())
+            .ok_or_else(|| AppError::Validation(format!("Unknown roll variable: @{}", name.trim())))?;
+        if value < 0 {
+            output.push_str(&format!("({})", value));
+        } else {
+            output.push_str(&value.to_string());
+        }
+    }
+
+    Ok(output)
+}
+
+/// Evaluate a dice-free expression such as `2+1` or `3-(1)`
+pub fn evaluate_constant(notation: &str) -> AppResult<i32> {
+    fn has_dice(expr: &Expr) -> bool {
+        match expr {
+            Expr::Number(_) => false,
+            Expr::Dice(_) => true,
+            Expr::Negate(inner) => has_dice(inner),
+            Expr::Binary(_, left, right) => has_dice(left) || has_dice(right),
+        }
+    }
+
+    let expr = parse_notation(notation)?;
+    if has_dice(&expr) {
+        return Err(AppError::Validation(format!("'{}' must not contain dice", notation)));
+    }
+
+    // No dice, so the generator is never used
+    let total = evaluate(&expr, &mut secure_rng(), &mut Vec::new(), &mut Vec::new())?;
+    i32::try_from(total).map_err(|_| AppError::OutOfRange("Modifier is too large".to_string()))
+}
+
+// ============================================================================
+// Duality Dice
+// ============================================================================
+
+/// Sides on the Hope and Fear dice
+pub const DUALITY_DIE_SIDES: i32 = 12;
+
+/// Classify a duality roll. Matching dice are a critical success regardless of
+/// difficulty; otherwise the higher die decides Hope vs. Fear and the total is
+/// compared against the difficulty (meeting it succeeds).
+pub fn classify_duality(hope: i32, fear: i32, total: i32, difficulty: Option<i32>) -> DualityOutcome {
+    if hope == fear {
+        return DualityOutcome::CriticalSuccess;
+    }
+
+    let with_hope = hope > fear;
+    match (difficulty.map(|d| total >= d), with_hope) {
+        (Some(true), true) => DualityOutcome::SuccessWithHope,
+        (Some(true), false) => DualityOutcome::SuccessWithFear,
+        (Some(false), true) => DualityOutcome::FailureWithHope,
+        (Some(false), false) => DualityOutcome::FailureWithFear,
+        (None, true) => DualityOutcome::WithHope,
+        (None, false) => DualityOutcome::WithFear,
+    }
+}
+
+/// Roll the Hope and Fear dice plus any advantage and bonus dice, then
+/// classify the result. Only the Hope and Fear dice decide criticals and
+/// Hope vs. Fear; the extra dice just move the total.
+pub fn roll_duality<R: Rng + ?Sized>(
+    modifier: i32,
+    difficulty: Option<i32>,
+    options: &RollOptions,
+    rng: &mut R,
+) -> AppResult<DualityResult> {
+    validate_options(options)?;
+
+    let hope = rng.gen_range(1..=DUALITY_DIE_SIDES);
+    let fear = rng.gen_range(1..=DUALITY_DIE_SIDES);
+
+    let mut dice = vec![
+        DieResult::new(DUALITY_DIE_SIDES, hope, 0, DieKind::Hope),
+        DieResult::new(DUALITY_DIE_SIDES, fear, 1, DieKind::Fear),
+    ];
+    dice.extend(roll_option_dice(options, 2, rng));
+
+    let total = dice.iter().map(|d| d.value()).sum::<i32>() + modifier;
+
+    Ok(DualityResult {
+        hope,
+        fear,
+        dice,
+        modifier,
+        total,
+        difficulty,
+        outcome: classify_duality(hope, fear, total, difficulty),
+    })
+}
+
+/// Notation stored for duality rolls, e.g. `duality+2`
+pub fn duality_notation(modifier: i32) -> String {
+    match modifier {
+        0 => "duality".to_string(),
+        m => format!("duality{:+}", m),
+    }
+}
+
+// ============================================================================
+// Random Number Generation
+// ============================================================================
+
+/// Generator behind every backend roll. ChaCha20 is a CSPRNG, and seeding it
+/// from a known value makes a roll exactly reproducible.
+pub type DiceRng = ChaCha20Rng;
+
+/// Unpredictable generator seeded from the operating system
+pub fn secure_rng() -> DiceRng {
+    ChaCha20Rng::from_entropy()
+}
+
+/// Deterministic generator for one roll: ChaCha20 seeded with
+/// `SHA-256("<seed>:<client_seed>:<nonce>")`. Anyone who knows the seed can
+/// replay the roll.
+pub fn derived_rng(seed: &str, client_seed: &str, nonce: i64) -> DiceRng {
+    let digest = Sha256::digest(format!("{}:{}:{}", seed, client_seed, nonce).as_bytes());
+    ChaCha20Rng::from_seed(digest.into())
+}
+
+/// Hex SHA-256 of a seed, published before the seed itself is revealed
+pub fn hash_seed(seed: &str) -> String {
+    hex::encode(Sha256::digest(seed.as_bytes()))
+}
+
+/// A fresh random 256-bit seed, hex encoded
+pub fn generate_seed() -> String {
+    let mut bytes = [0u8; 32];
+    secure_rng().fill(&mut bytes);
+    hex::encode(bytes)
+}
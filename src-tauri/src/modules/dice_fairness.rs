@@ -0,0 +1,396 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use super::database::Database;
+use super::dice::{get_dice_roll_by_id, DiceRoll, RollType};
+use super::dice_engine::{self, DiceRng, DieResult, RollOptions};
+use super::error::{AppError, AppResult};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How the rolls of a seed session are seeded. Roll `n` of a session uses
+/// `dice_engine::derived_rng(seed, client_seed, n)`, so once the seed is known
+/// every stored roll can be replayed.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RngMode {
+    /// Seed chosen by the GM and visible to everyone
+    Seeded,
+    /// Commit-reveal: only the seed's SHA-256 is published until the session ends
+    Committed,
+}
+
+impl RngMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RngMode::Seeded => "seeded",
+            RngMode::Committed => "committed",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "seeded" => RngMode::Seeded,
+            _ => RngMode::Committed,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct DiceRngSession {
+    pub id: String,
+    pub campaign_id: String,
+    pub mode: RngMode,
+    /// `None` while a committed session is still running
+    pub seed: Option<String>,
+    pub seed_hash: String,
+    pub client_seed: String,
+    /// Rolls made so far; also the nonce of the next roll
+    pub roll_count: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+/// Generator for one roll and the seed coordinates to record with it
+pub struct RollSeed {
+    pub session_id: Option<String>,
+    pub nonce: Option<i64>,
+    pub rng: DiceRng,
+}
+
+#[derive(Clone, Copy, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationStatus {
+    /// Replaying the roll from the revealed seed gives the stored dice
+    Verified,
+    /// The stored dice differ from the replay
+    Mismatch,
+    /// The session's seed has not been revealed yet
+    Pending,
+    /// Not rolled from a seed (e.g. saved from the dice bag)
+    Unverifiable,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct RollVerification {
+    pub roll_id: String,
+    pub status: VerificationStatus,
+    pub session_id: Option<String>,
+    pub nonce: Option<i64>,
+    pub expected_total: Option<i32>,
+    pub expected_dice: Option<Vec<DieResult>>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SessionVerification {
+    pub session: DiceRngSession,
+    /// The revealed seed hashes to the published `seed_hash`
+    pub seed_matches_hash: bool,
+    pub verified: u32,
+    /// Ids of rolls whose stored dice differ from the replay
+    pub mismatched: Vec<String>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn row_to_session(row: &Row) -> rusqlite::Result<DiceRngSession> {
+    let mode = RngMode::from_str(&row.get::<_, String>(2)?);
+    let ended_at: Option<String> = row.get(8)?;
+    let seed: String = row.get(3)?;
+
+    Ok(DiceRngSession {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        mode,
+        seed: (mode == RngMode::Seeded || ended_at.is_some()).then_some(seed),
+        seed_hash: row.get(4)?,
+        client_seed: row.get(5)?,
+        roll_count: row.get(6)?,
+        started_at: row.get(7)?,
+        ended_at,
+    })
+}
+
+const SELECT_COLUMNS: &str = "id, campaign_id, mode, seed, seed_hash, client_seed, next_nonce, started_at, ended_at";
+
+fn get_session_by_id(conn: &Connection, id: &str) -> AppResult<DiceRngSession> {
+    conn.query_row(
+        &format!("SELECT {} FROM dice_rng_sessions WHERE id = ?1", SELECT_COLUMNS),
+        params![id],
+        row_to_session,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Dice seed session not found: {}", id)))
+}
+
+fn get_active_session_id(conn: &Connection, campaign_id: &str) -> AppResult<Option<String>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM dice_rng_sessions WHERE campaign_id = ?1 AND ended_at IS NULL",
+            params![campaign_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Generator for the next roll in a campaign: a fresh CSPRNG, or inside a seed
+/// session the session's next derived generator. The latter claims a nonce,
+/// so call it in the transaction that saves the roll.
+pub fn next_roll_rng(conn: &Connection, campaign_id: &str) -> AppResult<RollSeed> {
+    let active = conn
+        .query_row(
+            "SELECT id, seed, client_seed, next_nonce FROM dice_rng_sessions
+             WHERE campaign_id = ?1 AND ended_at IS NULL",
+            params![campaign_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    match active {
+        Some((session_id, seed, client_seed, nonce)) => {
+            conn.execute(
+                "UPDATE dice_rng_sessions SET next_nonce = next_nonce + 1 WHERE id = ?1",
+                params![session_id],
+            )?;
+            Ok(RollSeed {
+                session_id: Some(session_id),
+                nonce: Some(nonce),
+                rng: dice_engine::derived_rng(&seed, &client_seed, nonce),
+            })
+        }
+        None => Ok(RollSeed {
+            session_id: None,
+            nonce: None,
+            rng: dice_engine::secure_rng(),
+        }),
+    }
+}
+
+/// Re-roll a stored roll from its seed, returning the dice and total it should have
+fn replay_roll(roll: &DiceRoll, seed: &str, client_seed: &str, nonce: i64) -> AppResult<(Vec<DieResult>, i32)> {
+    let options: RollOptions = match &roll.roll_options {
+        Some(json) => serde_json::from_str(json).map_err(|e| AppError::PersistenceError(e.to_string()))?,
+        None => RollOptions::default(),
+    };
+    let mut rng = dice_engine::derived_rng(seed, client_seed, nonce);
+
+    match roll.roll_type {
+        RollType::Standard => {
+            let result = dice_engine::roll_notation(&roll.notation, &options, &mut rng)?;
+            Ok((result.dice, result.total))
+        }
+        RollType::Duality => {
+            let result = dice_engine::roll_duality(roll.modifier, roll.difficulty, &options, &mut rng)?;
+            Ok((result.dice, result.total))
+        }
+    }
+}
+
+fn verify_roll(conn: &Connection, roll: &DiceRoll) -> AppResult<RollVerification> {
+    let mut verification = RollVerification {
+        roll_id: roll.id.clone(),
+        status: VerificationStatus::Unverifiable,
+        session_id: roll.rng_session_id.clone(),
+        nonce: roll.rng_nonce,
+        expected_total: None,
+        expected_dice: None,
+    };
+
+    let (Some(session_id), Some(nonce)) = (&roll.rng_session_id, roll.rng_nonce) else {
+        return Ok(verification);
+    };
+
+    let session = get_session_by_id(conn, session_id)?;
+    let Some(seed) = &session.seed else {
+        verification.status = VerificationStatus::Pending;
+        return Ok(verification);
+    };
+
+    let (dice, total) = replay_roll(roll, seed, &session.client_seed, nonce)?;
+    let stored: Vec<DieResult> = serde_json::from_str(&roll.dice_data).unwrap_or_default();
+
+    verification.status = if stored == dice && roll.total == total {
+        VerificationStatus::Verified
+    } else {
+        VerificationStatus::Mismatch
+    };
+    verification.expected_total = Some(total);
+    verification.expected_dice = Some(dice);
+
+    Ok(verification)
+}
+
+fn emit_session_update(app: &AppHandle, session: &DiceRngSession) {
+    let _ = app.emit("dice-rng-session-updated", session);
+}
+
+fn start_session(
+    conn: &Connection,
+    campaign_id: &str,
+    mode: RngMode,
+    seed: &str,
+    client_seed: &str,
+) -> AppResult<DiceRngSession> {
+    if get_active_session_id(conn, campaign_id)?.is_some() {
+        return Err(AppError::InvalidOperation(
+            "A dice seed session is already running; end it first".to_string(),
+        ));
+    }
+
+    let id = Uuid::new_v4().to_string();
+
+    conn.execute(
+        "INSERT INTO dice_rng_sessions (id, campaign_id, mode, seed, seed_hash, client_seed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            id,
+            campaign_id,
+            mode.as_str(),
+            seed,
+            dice_engine::hash_seed(seed),
+            client_seed
+        ],
+    )?;
+
+    get_session_by_id(conn, &id)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Make every following roll in the campaign reproducible from `seed` until
+/// the session ends
+#[tauri::command]
+pub fn start_seeded_dice_session(
+    db: State<Database>,
+    app: AppHandle,
+    campaign_id: String,
+    seed: String,
+) -> AppResult<DiceRngSession> {
+    if seed.is_empty() {
+        return Err(AppError::Validation("Seed cannot be empty".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let session = start_session(conn, &campaign_id, RngMode::Seeded, &seed, "")?;
+        emit_session_update(&app, &session);
+        Ok(session)
+    })
+}
+
+/// Commit to a secret server seed for the coming rolls and publish its hash.
+/// Players may contribute a `client_seed` so the GM can't pick a favourable
+/// server seed in advance.
+#[tauri::command]
+pub fn start_fairness_session(
+    db: State<Database>,
+    app: AppHandle,
+    campaign_id: String,
+    client_seed: Option<String>,
+) -> AppResult<DiceRngSession> {
+    db.with_conn(|conn| {
+        let seed = dice_engine::generate_seed();
+        let session = start_session(
+            conn,
+            &campaign_id,
+            RngMode::Committed,
+            &seed,
+            client_seed.as_deref().unwrap_or(""),
+        )?;
+        emit_session_update(&app, &session);
+        Ok(session)
+    })
+}
+
+/// End a seed session; a committed session's seed is revealed from now on
+#[tauri::command]
+pub fn end_dice_rng_session(db: State<Database>, app: AppHandle, id: String) -> AppResult<DiceRngSession> {
+    db.with_conn(|conn| {
+        conn.execute(
+            "UPDATE dice_rng_sessions SET ended_at = datetime('now') WHERE id = ?1 AND ended_at IS NULL",
+            params![id],
+        )?;
+
+        let session = get_session_by_id(conn, &id)?;
+        emit_session_update(&app, &session);
+        Ok(session)
+    })
+}
+
+/// Seed sessions for a campaign, newest first
+#[tauri::command]
+pub fn get_dice_rng_sessions(db: State<Database>, campaign_id: String) -> AppResult<Vec<DiceRngSession>> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM dice_rng_sessions WHERE campaign_id = ?1 ORDER BY started_at DESC",
+            SELECT_COLUMNS
+        ))?;
+
+        let sessions = stmt
+            .query_map(params![campaign_id], row_to_session)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    })
+}
+
+/// Replay one stored roll from its revealed seed
+#[tauri::command]
+pub fn verify_dice_roll(db: State<Database>, id: String) -> AppResult<RollVerification> {
+    db.with_conn(|conn| {
+        let roll = get_dice_roll_by_id(conn, &id)?;
+        verify_roll(conn, &roll)
+    })
+}
+
+/// Check a finished session's seed against its published hash and replay
+/// every roll made with it
+#[tauri::command]
+pub fn verify_dice_rng_session(db: State<Database>, id: String) -> AppResult<SessionVerification> {
+    db.with_conn(|conn| {
+        let session = get_session_by_id(conn, &id)?;
+        let Some(seed) = &session.seed else {
+            return Err(AppError::InvalidOperation(
+                "The seed is revealed when the session ends".to_string(),
+            ));
+        };
+
+        let seed_matches_hash = dice_engine::hash_seed(seed) == session.seed_hash;
+
+        let mut stmt = conn.prepare("SELECT id FROM dice_rolls WHERE rng_session_id = ?1 ORDER BY rng_nonce")?;
+        let roll_ids = stmt
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut verified = 0;
+        let mut mismatched = Vec::new();
+        for roll_id in roll_ids {
+            let roll = get_dice_roll_by_id(conn, &roll_id)?;
+            match verify_roll(conn, &roll)?.status {
+                VerificationStatus::Verified => verified += 1,
+                _ => mismatched.push(roll_id),
+            }
+        }
+
+        Ok(SessionVerification {
+            session,
+            seed_matches_hash,
+            verified,
+            mismatched,
+        })
+    })
+}
@@ -0,0 +1,612 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::dice_engine::{
+    self, BinaryOp, Compare, DiceTerm, Expr, KeepDrop, RollOptions, ADVANTAGE_DIE_SIDES, DUALITY_DIE_SIDES,
+    MAX_CHAIN,
+};
+use super::error::{AppError, AppResult};
+
+// ============================================================================
+// Limits
+// ============================================================================
+
+/// Rough cap on arithmetic per calculation, so huge pools fail fast instead of hanging
+const MAX_WORK: u64 = 50_000_000;
+
+/// Most distinct totals a distribution may have
+const MAX_SUPPORT: usize = 1_000_000;
+
+/// Explosion chains less likely than this are folded into the last step
+const NEGLIGIBLE: f64 = 1e-15;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Clone, Serialize)]
+pub struct OutcomeProbability {
+    pub total: i64,
+    pub probability: f64,
+    /// Chance of rolling this total or higher
+    pub at_least: f64,
+}
+
+/// Chances of each duality outcome. The `success_`/`failure_` splits are only
+/// set when a difficulty was given.
+#[derive(Clone, Serialize, Default)]
+pub struct DualityProbabilities {
+    pub critical: f64,
+    /// Higher Hope die, criticals excluded
+    pub with_hope: f64,
+    pub with_fear: f64,
+    pub success_with_hope: Option<f64>,
+    pub success_with_fear: Option<f64>,
+    pub failure_with_hope: Option<f64>,
+    pub failure_with_fear: Option<f64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProbabilityResult {
+    pub notation: String,
+    pub distribution: Vec<OutcomeProbability>,
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub difficulty: Option<i32>,
+    /// Chance to meet the difficulty (duality criticals always succeed)
+    pub success_chance: Option<f64>,
+    /// Natural 20 / natural 1 on a lone kept d20, as the engine flags them
+    pub crit_chance: Option<f64>,
+    pub fumble_chance: Option<f64>,
+    pub duality: Option<DualityProbabilities>,
+}
+
+/// Probability of every total from `min` upwards
+#[derive(Clone, Debug)]
+struct Distribution {
+    min: i64,
+    probs: Vec<f64>,
+}
+
+struct Budget {
+    remaining: u64,
+}
+
+impl Budget {
+    fn new() -> Self {
+        Budget { remaining: MAX_WORK }
+    }
+
+    fn spend(&mut self, work: usize) -> AppResult<()> {
+        self.remaining = self.remaining.checked_sub(work as u64).ok_or_else(too_complex)?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Distributions
+// ============================================================================
+
+impl Distribution {
+    fn constant(value: i64) -> Self {
+        Distribution { min: value, probs: vec![1.0] }
+    }
+
+    /// From per-face probabilities, face 1 first
+    fn from_faces(faces: Vec<f64>) -> Self {
+        Distribution { min: 1, probs: faces }.trimmed()
+    }
+
+    fn max(&self) -> i64 {
+        self.min + self.probs.len() as i64 - 1
+    }
+
+    fn get(&self, value: i64) -> f64 {
+        if value < self.min {
+            return 0.0;
+        }
+        self.probs.get((value - self.min) as usize).copied().unwrap_or(0.0)
+    }
+
+    /// Chance of `value` or more
+    fn at_least(&self, value: i64) -> f64 {
+        if value <= self.min {
+            return 1.0;
+        }
+        self.probs.iter().skip((value - self.min) as usize).sum()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.probs
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| **p > 0.0)
+            .map(move |(i, p)| (self.min + i as i64, *p))
+    }
+
+    /// Drop impossible totals from both ends
+    fn trimmed(mut self) -> Self {
+        let start = self.probs.iter().position(|p| *p > 0.0).unwrap_or(0);
+        let end = self.probs.iter().rposition(|p| *p > 0.0).map(|i| i + 1).unwrap_or(1);
+        self.min += start as i64;
+        self.probs = self.probs[start..end].to_vec();
+        self
+    }
+
+    fn negate(&self) -> AppResult<Self> {
+        // The old minimum becomes the new maximum, so it must negate too
+        self.min.checked_neg().ok_or_else(overflow)?;
+        Ok(Distribution {
+            min: self.max().checked_neg().ok_or_else(overflow)?,
+            probs: self.probs.iter().rev().copied().collect(),
+        })
+    }
+
+    fn shifted(&self, by: i64) -> AppResult<Self> {
+        self.max().checked_add(by).ok_or_else(overflow)?;
+        Ok(Distribution {
+            min: self.min.checked_add(by).ok_or_else(overflow)?,
+            probs: self.probs.clone(),
+        })
+    }
+
+    /// Distribution of the sum of two independent totals
+    fn add(&self, other: &Distribution, budget: &mut Budget) -> AppResult<Self> {
+        budget.spend(self.probs.len() * other.probs.len())?;
+        let len = self.probs.len() + other.probs.len() - 1;
+        if len > MAX_SUPPORT {
+            return Err(too_complex());
+        }
+        let min = self.min.checked_add(other.min).ok_or_else(overflow)?;
+        self.max().checked_add(other.max()).ok_or_else(overflow)?;
+
+        let mut probs = vec![0.0; len];
+        for (i, a) in self.probs.iter().enumerate().filter(|(_, a)| **a > 0.0) {
+            for (j, b) in other.probs.iter().enumerate() {
+                probs[i + j] += a * b;
+            }
+        }
+
+        Ok(Distribution { min, probs }.trimmed())
+    }
+
+    /// Sum of `count` independent copies, by repeated doubling
+    fn repeat(&self, count: usize, budget: &mut Budget) -> AppResult<Self> {
+        let mut result = Distribution::constant(0);
+        let mut power = self.clone();
+        let mut remaining = count;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.add(&power, budget)?;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                power = power.add(&power, budget)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Any other pairwise operation (multiplication, division)
+    fn combine<F>(&self, other: &Distribution, budget: &mut Budget, op: F) -> AppResult<Self>
+    where
+        F: Fn(i64, i64) -> AppResult<i64>,
+    {
+        budget.spend(self.probs.len() * other.probs.len())?;
+
+        let mut totals: BTreeMap<i64, f64> = BTreeMap::new();
+        for (a, pa) in self.iter() {
+            for (b, pb) in other.iter() {
+                *totals.entry(op(a, b)?).or_default() += pa * pb;
+            }
+        }
+
+        let (&min, _) = totals.iter().next().expect("distributions are never empty");
+        let (&max, _) = totals.iter().next_back().expect("distributions are never empty");
+        let len = (max - min + 1) as usize;
+        if len > MAX_SUPPORT {
+            return Err(too_complex());
+        }
+
+        let mut probs = vec![0.0; len];
+        for (total, p) in totals {
+            probs[(total - min) as usize] = p;
+        }
+        Ok(Distribution { min, probs })
+    }
+
+    fn mean(&self) -> f64 {
+        self.iter().map(|(v, p)| v as f64 * p).sum()
+    }
+
+    fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        self.iter()
+            .map(|(v, p)| (v as f64 - mean).powi(2) * p)
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+fn too_complex() -> AppError {
+    AppError::Validation("Too many possible outcomes to calculate exactly".to_string())
+}
+
+fn overflow() -> AppError {
+    AppError::OutOfRange("Roll total is too large".to_string())
+}
+
+fn uniform(sides: i32) -> Distribution {
+    Distribution::from_faces(vec![1.0 / sides as f64; sides as usize])
+}
+
+/// One die after the term's reroll rule, mirroring the engine's attempt cap
+fn single_die(term: &DiceTerm) -> Distribution {
+    let sides = term.sides as usize;
+    let face = 1.0 / sides as f64;
+
+    let Some(reroll) = &term.reroll else {
+        return uniform(term.sides);
+    };
+
+    let matching = reroll.compare.face_count(term.sides) as f64 * face;
+    // Chance a face survives: first roll or any reroll that lands on it before the cap
+    let attempts = if reroll.once { 1 } else { MAX_CHAIN };
+    let keep_weight: f64 = (0..=attempts).map(|t| matching.powi(t as i32)).sum::<f64>() * face;
+    let stuck_weight = matching.powi(attempts as i32) * face;
+
+    Distribution::from_faces(
+        (1..=term.sides)
+            .map(|f| if reroll.compare.matches(f) { stuck_weight } else { keep_weight })
+            .collect(),
+    )
+}
+
+/// A die plus everything it explodes into, summed
+fn exploding_die(die: &Distribution, explode: Compare, budget: &mut Budget) -> AppResult<Distribution> {
+    let explode_chance: f64 = die.iter().filter(|(v, _)| explode.matches(*v as i32)).map(|(_, p)| p).sum();
+
+    // Deeper chains than this carry no measurable probability
+    let mut depth = MAX_CHAIN;
+    if explode_chance > 0.0 {
+        let needed = (NEGLIGIBLE.ln() / explode_chance.ln()).ceil();
+        if needed.is_finite() && needed >= 0.0 {
+            depth = depth.min(needed as usize);
+        }
+    }
+
+    // Build from the deepest explosion outwards; the last die can't explode
+    let mut chain = die.clone();
+    for _ in 0..depth {
+        let min = die.min;
+        let max = die.max() + chain.max();
+        let len = (max - min + 1) as usize;
+        if len > MAX_SUPPORT {
+            return Err(too_complex());
+        }
+
+        let mut probs = vec![0.0; len];
+        for (value, p) in die.iter() {
+            if explode.matches(value as i32) {
+                budget.spend(chain.probs.len())?;
+                let start = (value + chain.min - min) as usize;
+                for (i, q) in chain.probs.iter().enumerate() {
+                    probs[start + i] += p * q;
+                }
+            } else {
+                probs[(value - min) as usize] += p;
+            }
+        }
+        chain = Distribution { min, probs }.trimmed();
+    }
+
+    Ok(chain)
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Sum of the `keep` highest (or lowest) of `count` dice. Walks the faces from
+/// the kept end; at each face the number of remaining dice showing it is
+/// binomial, conditioned on none of them beating the faces already passed.
+fn kept_sum(die: &Distribution, count: usize, keep: usize, highest: bool, budget: &mut Budget) -> AppResult<Distribution> {
+    if keep == 0 {
+        return Ok(Distribution::constant(0));
+    }
+
+    let mut faces: Vec<(i64, f64)> = die.iter().collect();
+    if highest {
+        faces.reverse();
+    }
+
+    // Die faces are positive, so kept sums run from 0 to keep × highest face
+    let width = (keep as i64 * die.max() + 1) as usize;
+    if width > MAX_SUPPORT {
+        return Err(too_complex());
+    }
+
+    // states[assigned][sum], for fewer than `keep` dice assigned so far
+    let mut states = vec![vec![0.0; width]; keep];
+    states[0][0] = 1.0;
+    let mut finished = vec![0.0; width];
+    let mut remaining_mass = 1.0;
+
+    for (value, p) in faces {
+        // Chance a not-yet-assigned die shows this face, given it doesn't beat the faces passed
+        let q = if remaining_mass <= 0.0 { 1.0 } else { (p / remaining_mass).min(1.0) };
+        remaining_mass -= p;
+
+        let mut next = vec![vec![0.0; width]; keep];
+        for (assigned, row) in states.iter().enumerate() {
+            let left = count - assigned;
+            budget.spend(width * (left + 1))?;
+            for (index, &mass) in row.iter().enumerate().filter(|(_, m)| **m > 0.0) {
+                for showing in 0..=left {
+                    let chance = binomial(left, showing) * q.powi(showing as i32) * (1.0 - q).powi((left - showing) as i32);
+                    if chance == 0.0 {
+                        continue;
+                    }
+                    let kept = showing.min(keep - assigned);
+                    let sum_index = (index as i64 + kept as i64 * value) as usize;
+                    if assigned + showing >= keep {
+                        finished[sum_index] += mass * chance;
+                    } else {
+                        next[assigned + showing][sum_index] += mass * chance;
+                    }
+                }
+            }
+        }
+        states = next;
+    }
+
+    Ok(Distribution { min: 0, probs: finished }.trimmed())
+}
+
+fn term_distribution(term: &DiceTerm, budget: &mut Budget) -> AppResult<Distribution> {
+    let die = single_die(term);
+    let count = term.count as usize;
+
+    match (term.keep, term.explode) {
+        (Some(_), Some(_)) => Err(AppError::Validation(format!(
+            "{}: exact odds aren't available for exploding dice with keep/drop",
+            term
+        ))),
+        (None, Some(explode)) => exploding_die(&die, explode, budget)?.repeat(count, budget),
+        (None, None) => die.repeat(count, budget),
+        (Some(keep), None) => {
+            let clamp = |n: i32| (n.max(0) as usize).min(count);
+            match keep {
+                KeepDrop::KeepHighest(n) => kept_sum(&die, count, clamp(n), true, budget),
+                KeepDrop::KeepLowest(n) => kept_sum(&die, count, clamp(n), false, budget),
+                KeepDrop::DropHighest(n) => kept_sum(&die, count, count - clamp(n), false, budget),
+                KeepDrop::DropLowest(n) => kept_sum(&die, count, count - clamp(n), true, budget),
+            }
+        }
+    }
+}
+
+fn expr_distribution(expr: &Expr, budget: &mut Budget) -> AppResult<Distribution> {
+    match expr {
+        Expr::Number(n) => Ok(Distribution::constant(*n as i64)),
+        Expr::Dice(term) => term_distribution(term, budget),
+        Expr::Negate(inner) => expr_distribution(inner, budget)?.negate(),
+        Expr::Binary(op, left, right) => {
+            let left = expr_distribution(left, budget)?;
+            let right = expr_distribution(right, budget)?;
+            match op {
+                BinaryOp::Add => left.add(&right, budget),
+                BinaryOp::Subtract => left.add(&right.negate()?, budget),
+                BinaryOp::Multiply => left.combine(&right, budget, |a, b| a.checked_mul(b).ok_or_else(overflow)),
+                BinaryOp::Divide => left.combine(&right, budget, |a, b| {
+                    if b == 0 {
+                        return Err(AppError::InvalidOperation("Division by zero is possible".to_string()));
+                    }
+                    // Round down, like the engine
+                    let quotient = a.checked_div(b).ok_or_else(overflow)?;
+                    if a.checked_rem(b).ok_or_else(overflow)? != 0 && (a < 0) != (b < 0) {
+                        Ok(quotient - 1)
+                    } else {
+                        Ok(quotient)
+                    }
+                }),
+            }
+        }
+    }
+}
+
+/// Advantage/disadvantage and bonus dice added on top of a roll
+fn options_distribution(options: &RollOptions, budget: &mut Budget) -> AppResult<Distribution> {
+    dice_engine::validate_options(options)?;
+    let mut dist = Distribution::constant(0);
+
+    let net = options.advantage as i64 - options.disadvantage as i64;
+    if net > 0 {
+        dist = dist.add(&uniform(ADVANTAGE_DIE_SIDES), budget)?;
+    } else if net < 0 {
+        dist = dist.add(&uniform(ADVANTAGE_DIE_SIDES).negate()?, budget)?;
+    }
+
+    for bonus in &options.bonus_dice {
+        dist = dist.add(&uniform(bonus.sides), budget)?;
+    }
+
+    Ok(dist)
+}
+
+/// The lone dice group, if the expression has exactly one and it keeps a single d20
+fn lone_d20(expr: &Expr) -> Option<&DiceTerm> {
+    fn collect<'a>(expr: &'a Expr, terms: &mut Vec<&'a DiceTerm>) {
+        match expr {
+            Expr::Number(_) => {}
+            Expr::Dice(term) => terms.push(term),
+            Expr::Negate(inner) => collect(inner, terms),
+            Expr::Binary(_, left, right) => {
+                collect(left, terms);
+                collect(right, terms);
+            }
+        }
+    }
+
+    let mut terms = Vec::new();
+    collect(expr, &mut terms);
+    let [term] = terms.as_slice() else {
+        return None;
+    };
+
+    let kept = match term.keep {
+        Some(KeepDrop::KeepHighest(n)) | Some(KeepDrop::KeepLowest(n)) => n.clamp(0, term.count),
+        Some(KeepDrop::DropHighest(n)) | Some(KeepDrop::DropLowest(n)) => term.count - n.clamp(0, term.count),
+        None => term.count,
+    };
+    (term.sides == 20 && kept == 1 && term.explode.is_none()).then_some(*term)
+}
+
+/// `duality`, `duality+2` or `duality-1` → the modifier
+fn parse_duality_notation(notation: &str) -> AppResult<Option<i32>> {
+    let compact: String = notation
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let Some(rest) = compact.strip_prefix("duality") else {
+        return Ok(None);
+    };
+    if rest.is_empty() {
+        return Ok(Some(0));
+    }
+    dice_engine::evaluate_constant(&format!("0{}", rest)).map(Some)
+}
+
+fn summarize(notation: &str, dist: &Distribution, difficulty: Option<i32>) -> ProbabilityResult {
+    let mut at_least: f64 = 1.0;
+    let distribution = dist
+        .iter()
+        .map(|(total, probability)| {
+            let entry = OutcomeProbability {
+                total,
+                probability,
+                at_least: at_least.max(0.0),
+            };
+            at_least -= probability;
+            entry
+        })
+        .collect();
+
+    ProbabilityResult {
+        notation: notation.trim().to_string(),
+        distribution,
+        min: dist.min,
+        max: dist.max(),
+        mean: dist.mean(),
+        std_dev: dist.std_dev(),
+        difficulty,
+        success_chance: difficulty.map(|d| dist.at_least(d as i64)),
+        crit_chance: None,
+        fumble_chance: None,
+        duality: None,
+    }
+}
+
+fn duality_probability(
+    notation: &str,
+    modifier: i32,
+    difficulty: Option<i32>,
+    options: &RollOptions,
+    budget: &mut Budget,
+) -> AppResult<ProbabilityResult> {
+    let extra = options_distribution(options, budget)?.shifted(modifier as i64)?;
+    let face = 1.0 / (DUALITY_DIE_SIDES * DUALITY_DIE_SIDES) as f64;
+
+    let mut duality = DualityProbabilities::default();
+    let mut split = [0.0; 4]; // success/failure × hope/fear
+    let mut totals = vec![0.0; 2 * DUALITY_DIE_SIDES as usize - 1];
+
+    for hope in 1..=DUALITY_DIE_SIDES {
+        for fear in 1..=DUALITY_DIE_SIDES {
+            totals[(hope + fear - 2) as usize] += face;
+
+            if hope == fear {
+                duality.critical += face;
+                continue;
+            }
+            let with_hope = hope > fear;
+            if with_hope {
+                duality.with_hope += face;
+            } else {
+                duality.with_fear += face;
+            }
+            if let Some(difficulty) = difficulty {
+                let success = extra.at_least(difficulty as i64 - (hope + fear) as i64);
+                let base = if with_hope { 0 } else { 1 };
+                split[base] += face * success;
+                split[base + 2] += face * (1.0 - success);
+            }
+        }
+    }
+
+    if difficulty.is_some() {
+        duality.success_with_hope = Some(split[0]);
+        duality.success_with_fear = Some(split[1]);
+        duality.failure_with_hope = Some(split[2]);
+        duality.failure_with_fear = Some(split[3]);
+    }
+
+    let dice = Distribution { min: 2, probs: totals };
+    let dist = dice.add(&extra, budget)?;
+
+    let mut result = summarize(notation, &dist, difficulty);
+    // Matching dice succeed no matter the total
+    result.success_chance = difficulty.map(|_| duality.critical + split[0] + split[1]);
+    result.duality = Some(duality);
+    Ok(result)
+}
+
+/// Exact mean of an expression, for expressions `dice_engine::expected_value`
+/// can't handle in closed form (keep/drop, exploding, rerolls, division)
+pub fn exact_mean(expr: &Expr) -> AppResult<f64> {
+    Ok(expr_distribution(expr, &mut Budget::new())?.mean())
+}
+
+/// Exact outcome distribution of a notation (or `duality±N`) with optional
+/// advantage and bonus dice, and the chance to meet `difficulty`
+pub fn calculate_probability(
+    notation: &str,
+    difficulty: Option<i32>,
+    options: &RollOptions,
+) -> AppResult<ProbabilityResult> {
+    let mut budget = Budget::new();
+
+    if let Some(modifier) = parse_duality_notation(notation)? {
+        return duality_probability(notation, modifier, difficulty, options, &mut budget);
+    }
+
+    let expr = dice_engine::parse_notation(notation)?;
+    let dist = expr_distribution(&expr, &mut budget)?.add(&options_distribution(options, &mut budget)?, &mut budget)?;
+
+    let mut result = summarize(notation, &dist, difficulty);
+    if let Some(term) = lone_d20(&expr) {
+        let kept = term_distribution(term, &mut budget)?;
+        result.crit_chance = Some(kept.get(20));
+        result.fumble_chance = Some(kept.get(1));
+    }
+    Ok(result)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Exact odds for a roll before making it: every possible total, the chance
+/// to meet `difficulty`, and for `duality` notation the with-Hope / with-Fear /
+/// critical split. `options` adds advantage and bonus dice as in `roll_dice`.
+#[tauri::command]
+pub fn calculate_dice_probability(
+    notation: String,
+    difficulty: Option<i32>,
+    options: Option<RollOptions>,
+) -> AppResult<ProbabilityResult> {
+    calculate_probability(&notation, difficulty, &options.unwrap_or_default())
+}
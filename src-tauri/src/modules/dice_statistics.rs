@@ -0,0 +1,400 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+use super::database::Database;
+use super::dice::{query_dice_rolls, DiceRoll, DiceRollFilter, RollType};
+use super::dice_engine::{self, DieKind, DieResult, DualityOutcome};
+use super::dice_probability;
+use super::error::AppResult;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Chi-square p-value below which a die is flagged as suspicious
+const FAIRNESS_SIGNIFICANCE: f64 = 0.01;
+
+/// Each face should be expected at least this many times for the test to mean anything
+const MIN_EXPECTED_PER_FACE: f64 = 5.0;
+
+#[derive(Clone, Serialize)]
+pub struct DiceStatistics {
+    pub total_rolls: u32,
+    pub crit_count: u32,
+    pub fumble_count: u32,
+    pub crit_rate: f64,
+    pub fumble_rate: f64,
+    pub notations: Vec<NotationStatistics>,
+    pub duality: DualityStatistics,
+    pub fairness: Vec<DieFairness>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TotalCount {
+    pub total: i32,
+    pub count: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct NotationStatistics {
+    pub notation: String,
+    pub count: u32,
+    pub average: f64,
+    /// Mean of each roll's expected total; `None` if any roll's notation was
+    /// too large to work out exactly
+    pub expected_average: Option<f64>,
+    pub min: i32,
+    pub max: i32,
+    pub crit_count: u32,
+    pub fumble_count: u32,
+    pub distribution: Vec<TotalCount>,
+}
+
+#[derive(Clone, Serialize, Default)]
+pub struct DualityStatistics {
+    pub rolls: u32,
+    pub with_hope: u32,
+    pub with_fear: u32,
+    pub critical: u32,
+    pub successes: u32,
+    pub failures: u32,
+    /// Share of duality rolls that came up with Hope (criticals included)
+    pub hope_ratio: Option<f64>,
+}
+
+/// Chi-square goodness-of-fit test of one die size against a uniform distribution
+#[derive(Clone, Serialize)]
+pub struct DieFairness {
+    pub sides: i32,
+    pub rolls: u32,
+    /// Count per face, index 0 = face 1
+    pub face_counts: Vec<u32>,
+    pub chi_square: f64,
+    pub degrees_of_freedom: i32,
+    pub p_value: f64,
+    /// Enough rolls for every face to be expected at least 5 times
+    pub sufficient_sample: bool,
+    /// Sample is sufficient and the p-value is below 0.01
+    pub suspicious: bool,
+}
+
+#[derive(Default)]
+struct NotationAccumulator {
+    count: u32,
+    sum: i64,
+    expected_sum: Option<f64>,
+    min: i32,
+    max: i32,
+    crit_count: u32,
+    fumble_count: u32,
+    distribution: BTreeMap<i32, u32>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn parse_dice(roll: &DiceRoll) -> Vec<DieResult> {
+    serde_json::from_str(&roll.dice_data).unwrap_or_default()
+}
+
+/// Expected total of a notation, falling back to the exact distribution for
+/// keep/drop, exploding and reroll modifiers
+fn expected_notation_value(notation: &str) -> Option<f64> {
+    let expr = dice_engine::parse_notation(notation).ok()?;
+    dice_engine::expected_value(&expr).or_else(|| dice_probability::exact_mean(&expr).ok())
+}
+
+/// Expected total for a single stored roll, including its extra dice.
+/// `cache` holds notation expectations already worked out.
+fn expected_total(roll: &DiceRoll, dice: &[DieResult], cache: &mut HashMap<String, Option<f64>>) -> Option<f64> {
+    let base = match roll.roll_type {
+        RollType::Duality => {
+            let mean = (dice_engine::DUALITY_DIE_SIDES as f64 + 1.0) / 2.0;
+            2.0 * mean + roll.modifier as f64
+        }
+        RollType::Standard => cache
+            .entry(roll.notation.clone())
+            .or_insert_with(|| expected_notation_value(&roll.notation))
+            .as_ref()
+            .copied()?,
+    };
+
+    let extra: f64 = dice
+        .iter()
+        .filter(|d| matches!(d.kind, DieKind::Advantage | DieKind::Disadvantage | DieKind::Bonus))
+        .map(dice_engine::expected_die_value)
+        .sum();
+
+    Some(base + extra)
+}
+
+fn normalize_notation(notation: &str) -> String {
+    notation
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn rate(count: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+fn build_notation_statistics(rolls: &[(DiceRoll, Vec<DieResult>)]) -> Vec<NotationStatistics> {
+    let mut by_notation: HashMap<String, NotationAccumulator> = HashMap::new();
+    let mut expectations: HashMap<String, Option<f64>> = HashMap::new();
+
+    for (roll, dice) in rolls {
+        let acc = by_notation
+            .entry(normalize_notation(&roll.notation))
+            .or_insert_with(|| NotationAccumulator {
+                expected_sum: Some(0.0),
+                min: i32::MAX,
+                max: i32::MIN,
+                ..Default::default()
+            });
+
+        acc.count += 1;
+        acc.sum += roll.total as i64;
+        acc.min = acc.min.min(roll.total);
+        acc.max = acc.max.max(roll.total);
+        acc.crit_count += roll.is_crit as u32;
+        acc.fumble_count += roll.is_fumble as u32;
+        *acc.distribution.entry(roll.total).or_default() += 1;
+        acc.expected_sum = match (acc.expected_sum, expected_total(roll, dice, &mut expectations)) {
+            (Some(sum), Some(expected)) => Some(sum + expected),
+            _ => None,
+        };
+    }
+
+    let mut stats: Vec<NotationStatistics> = by_notation
+        .into_iter()
+        .map(|(notation, acc)| NotationStatistics {
+            notation,
+            count: acc.count,
+            average: acc.sum as f64 / acc.count as f64,
+            expected_average: acc.expected_sum.map(|sum| sum / acc.count as f64),
+            min: acc.min,
+            max: acc.max,
+            crit_count: acc.crit_count,
+            fumble_count: acc.fumble_count,
+            distribution: acc
+                .distribution
+                .into_iter()
+                .map(|(total, count)| TotalCount { total, count })
+                .collect(),
+        })
+        .collect();
+
+    // Most-used notations first
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.notation.cmp(&b.notation)));
+    stats
+}
+
+fn build_duality_statistics(rolls: &[(DiceRoll, Vec<DieResult>)]) -> DualityStatistics {
+    let mut stats = DualityStatistics::default();
+
+    for outcome in rolls.iter().filter_map(|(roll, _)| roll.outcome) {
+        stats.rolls += 1;
+        if outcome == DualityOutcome::CriticalSuccess {
+            stats.critical += 1;
+        }
+        if outcome.is_with_hope() {
+            stats.with_hope += 1;
+        } else {
+            stats.with_fear += 1;
+        }
+        match outcome.is_success() {
+            Some(true) => stats.successes += 1,
+            Some(false) => stats.failures += 1,
+            None => {}
+        }
+    }
+
+    if stats.rolls > 0 {
+        stats.hope_ratio = Some(rate(stats.with_hope, stats.rolls));
+    }
+    stats
+}
+
+fn build_fairness(rolls: &[(DiceRoll, Vec<DieResult>)]) -> Vec<DieFairness> {
+    let mut faces: BTreeMap<i32, Vec<u32>> = BTreeMap::new();
+
+    // Rerolled dice are excluded: the reroll rule skews their distribution on purpose
+    for die in rolls.iter().flat_map(|(_, dice)| dice.iter()) {
+        if die.rerolled || die.sides < 2 || die.result < 1 || die.result > die.sides {
+            continue;
+        }
+        let counts = faces
+            .entry(die.sides)
+            .or_insert_with(|| vec![0; die.sides as usize]);
+        counts[(die.result - 1) as usize] += 1;
+    }
+
+    faces
+        .into_iter()
+        .map(|(sides, face_counts)| {
+            let rolls: u32 = face_counts.iter().sum();
+            let expected = rolls as f64 / sides as f64;
+            let chi_square: f64 = face_counts
+                .iter()
+                .map(|&observed| (observed as f64 - expected).powi(2) / expected)
+                .sum();
+            let degrees_of_freedom = sides - 1;
+            let p_value = chi_square_p_value(chi_square, degrees_of_freedom as f64);
+            let sufficient_sample = expected >= MIN_EXPECTED_PER_FACE;
+
+            DieFairness {
+                sides,
+                rolls,
+                face_counts,
+                chi_square,
+                degrees_of_freedom,
+                p_value,
+                sufficient_sample,
+                suspicious: sufficient_sample && p_value < FAIRNESS_SIGNIFICANCE,
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Chi-square Distribution
+// ============================================================================
+
+/// Upper-tail probability of a chi-square statistic with `k` degrees of freedom
+fn chi_square_p_value(x: f64, k: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    regularized_gamma_q(k / 2.0, x / 2.0).clamp(0.0, 1.0)
+}
+
+/// Lanczos approximation of ln Γ(x)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000000000190015;
+    let mut y = x;
+    for c in COEFFICIENTS {
+        y += 1.0;
+        series += c / y;
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+/// Regularized upper incomplete gamma function Q(a, x)
+fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 500;
+    const EPSILON: f64 = 1e-12;
+    const TINY: f64 = 1e-300;
+
+    let log_prefix = -x + a * x.ln() - ln_gamma(a);
+
+    if x < a + 1.0 {
+        // Series expansion of P(a, x), then Q = 1 - P
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut n = a;
+        for _ in 0..MAX_ITERATIONS {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        1.0 - sum * log_prefix.exp()
+    } else {
+        // Continued fraction for Q(a, x) (modified Lentz)
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        h * log_prefix.exp()
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Roll analytics for a campaign: per-notation distributions and averages
+/// vs. expected values, crit/fumble rates, Hope-vs-Fear ratios and a
+/// chi-square fairness test per die size.
+///
+/// `from`/`to` limit the rolls to `[from, to)` (SQLite datetimes in UTC, so a
+/// session can be selected by its start and end) and `player_character_id`
+/// to one roller.
+#[tauri::command]
+pub fn get_dice_statistics(
+    db: State<Database>,
+    campaign_id: String,
+    from: Option<String>,
+    to: Option<String>,
+    player_character_id: Option<String>,
+) -> AppResult<DiceStatistics> {
+    db.with_conn(|conn| {
+        let filter = DiceRollFilter {
+            from,
+            to,
+            player_character_id,
+            ..Default::default()
+        };
+        let rolls: Vec<(DiceRoll, Vec<DieResult>)> = query_dice_rolls(conn, &campaign_id, &filter)?
+            .into_iter()
+            .map(|roll| {
+                let dice = parse_dice(&roll);
+                (roll, dice)
+            })
+            .collect();
+
+        let total_rolls = rolls.len() as u32;
+        let crit_count = rolls.iter().filter(|(r, _)| r.is_crit).count() as u32;
+        let fumble_count = rolls.iter().filter(|(r, _)| r.is_fumble).count() as u32;
+
+        Ok(DiceStatistics {
+            total_rolls,
+            crit_count,
+            fumble_count,
+            crit_rate: rate(crit_count, total_rolls),
+            fumble_rate: rate(fumble_count, total_rolls),
+            notations: build_notation_statistics(&rolls),
+            duality: build_duality_statistics(&rolls),
+            fairness: build_fairness(&rolls),
+        })
+    })
+}
@@ -0,0 +1,614 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{Emitter, State};
+use uuid::Uuid;
+
+use super::adversary_library::{get_template_by_id, spawn_template};
+use super::database::Database;
+use super::entity::{
+    emit_entities_update, get_entity_by_id, get_required_campaign_id, AdversaryRole, Entity, EntityType,
+};
+use super::error::{AppError, AppResult};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// The rulebook's "easier or shorter" and "harder or longer" fight options
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EncounterIntensity {
+    Easier,
+    #[default]
+    Standard,
+    Harder,
+}
+
+impl EncounterIntensity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EncounterIntensity::Easier => "easier",
+            EncounterIntensity::Standard => "standard",
+            EncounterIntensity::Harder => "harder",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "easier" => EncounterIntensity::Easier,
+            "harder" => EncounterIntensity::Harder,
+            _ => EncounterIntensity::Standard,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EncounterStatus {
+    /// Prepped ahead of time, its entities hidden from players
+    #[default]
+    Staged,
+    Active,
+    Completed,
+}
+
+impl EncounterStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EncounterStatus::Staged => "staged",
+            EncounterStatus::Active => "active",
+            EncounterStatus::Completed => "completed",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "active" => EncounterStatus::Active,
+            "completed" => EncounterStatus::Completed,
+            _ => EncounterStatus::Staged,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct BudgetAdjustment {
+    pub reason: String,
+    pub points: i32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct RoleCost {
+    pub role: AdversaryRole,
+    pub count: i32,
+    pub points: i32,
+}
+
+/// Battle Points available for the active party against those spent on the
+/// encounter's adversaries
+#[derive(Clone, Serialize, Debug)]
+pub struct EncounterBudget {
+    pub party_size: i32,
+    pub party_tier: i32,
+    /// (3 × party size) + 2
+    pub base_points: i32,
+    pub adjustments: Vec<BudgetAdjustment>,
+    pub available_points: i32,
+    pub spent_points: i32,
+    pub role_costs: Vec<RoleCost>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct Encounter {
+    pub id: String,
+    pub campaign_id: String,
+    pub name: String,
+    pub notes: Option<String>,
+    pub intensity: EncounterIntensity,
+    /// +1d4 (or a static +2) to every adversary's damage rolls
+    pub damage_boost: bool,
+    pub status: EncounterStatus,
+    pub entity_ids: Vec<String>,
+    pub budget: EncounterBudget,
+    pub activated_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Clone, Serialize)]
+struct EncountersPayload {
+    encounters: Vec<Encounter>,
+    campaign_id: String,
+}
+
+// ============================================================================
+// Battle Points
+// ============================================================================
+
+/// Battle Point cost of one adversary of a role. Minions are costed per
+/// group the size of the party instead.
+fn role_cost(role: AdversaryRole) -> i32 {
+    match role {
+        AdversaryRole::Minion => 0,
+        AdversaryRole::Social | AdversaryRole::Support => 1,
+        AdversaryRole::Horde | AdversaryRole::Ranged | AdversaryRole::Skulk | AdversaryRole::Standard => 2,
+        AdversaryRole::Leader => 3,
+        AdversaryRole::Bruiser => 4,
+        AdversaryRole::Solo => 5,
+    }
+}
+
+fn tier_for_level(level: i32) -> i32 {
+    match level {
+        i32::MIN..=1 => 1,
+        2..=4 => 2,
+        5..=7 => 3,
+        _ => 4,
+    }
+}
+
+/// Score an encounter's adversaries against the party's budget, applying
+/// the rulebook's adjustments. NPCs don't cost points.
+pub fn calculate_budget(
+    party_levels: &[i32],
+    entities: &[Entity],
+    intensity: EncounterIntensity,
+    damage_boost: bool,
+) -> EncounterBudget {
+    let party_size = party_levels.len() as i32;
+    let party_tier = if party_levels.is_empty() {
+        1
+    } else {
+        let average = party_levels.iter().sum::<i32>() as f64 / party_size as f64;
+        tier_for_level(average.round() as i32)
+    };
+    let base_points = 3 * party_size + 2;
+
+    let adversaries: Vec<&Entity> = entities
+        .iter()
+        .filter(|e| e.entity_type == EntityType::Adversary)
+        .collect();
+
+    let mut counts: HashMap<&'static str, (AdversaryRole, i32)> = HashMap::new();
+    for adversary in &adversaries {
+        let role = adversary.stat_block.role;
+        counts.entry(role.as_str()).or_insert((role, 0)).1 += 1;
+    }
+    let mut role_costs: Vec<RoleCost> = counts
+        .into_values()
+        .map(|(role, count)| RoleCost {
+            role,
+            count,
+            points: if role == AdversaryRole::Minion {
+                // One point per group of Minions equal in size to the party
+                (count + party_size.max(1) - 1) / party_size.max(1)
+            } else {
+                count * role_cost(role)
+            },
+        })
+        .collect();
+    role_costs.sort_by(|a, b| b.points.cmp(&a.points).then(a.role.as_str().cmp(b.role.as_str())));
+    let spent_points = role_costs.iter().map(|c| c.points).sum();
+
+    let mut adjustments = Vec::new();
+    match intensity {
+        EncounterIntensity::Easier => adjustments.push(BudgetAdjustment {
+            reason: "Easier or shorter fight".to_string(),
+            points: -1,
+        }),
+        EncounterIntensity::Harder => adjustments.push(BudgetAdjustment {
+            reason: "Harder or longer fight".to_string(),
+            points: 2,
+        }),
+        EncounterIntensity::Standard => {}
+    }
+    let solos = adversaries.iter().filter(|a| a.stat_block.role == AdversaryRole::Solo).count();
+    if solos >= 2 {
+        adjustments.push(BudgetAdjustment {
+            reason: "Two or more Solo adversaries".to_string(),
+            points: -2,
+        });
+    }
+    if damage_boost {
+        adjustments.push(BudgetAdjustment {
+            reason: "+1d4 damage for all adversaries".to_string(),
+            points: -2,
+        });
+    }
+    if adversaries.iter().any(|a| a.stat_block.tier < party_tier) {
+        adjustments.push(BudgetAdjustment {
+            reason: "Adversary from a lower tier".to_string(),
+            points: 1,
+        });
+    }
+    let heavy_roles = [AdversaryRole::Bruiser, AdversaryRole::Horde, AdversaryRole::Leader, AdversaryRole::Solo];
+    if !adversaries.iter().any(|a| heavy_roles.contains(&a.stat_block.role)) {
+        adjustments.push(BudgetAdjustment {
+            reason: "No Bruisers, Hordes, Leaders or Solos".to_string(),
+            points: 1,
+        });
+    }
+    let available_points = base_points + adjustments.iter().map(|a| a.points).sum::<i32>();
+
+    let mut warnings = Vec::new();
+    if party_size == 0 {
+        warnings.push("No active player characters; mark the party active to budget for them".to_string());
+    }
+    for adversary in &adversaries {
+        if adversary.stat_block.tier > party_tier {
+            warnings.push(format!(
+                "{} is tier {}, above the party's tier {}",
+                adversary.name, adversary.stat_block.tier, party_tier
+            ));
+        }
+    }
+    if spent_points > available_points {
+        warnings.push(format!("Over budget by {} Battle Points", spent_points - available_points));
+    } else if spent_points < available_points {
+        warnings.push(format!("Under budget by {} Battle Points", available_points - spent_points));
+    }
+
+    EncounterBudget {
+        party_size,
+        party_tier,
+        base_points,
+        adjustments,
+        available_points,
+        spent_points,
+        role_costs,
+        warnings,
+    }
+}
+
+// ============================================================================
+// Database Helpers
+// ============================================================================
+
+const SELECT_COLUMNS: &str = "id, campaign_id, name, notes, intensity, damage_boost, status, activated_at, created_at, updated_at";
+
+/// Encounter row without its entities or budget filled in
+fn row_to_encounter(row: &Row) -> rusqlite::Result<Encounter> {
+    Ok(Encounter {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        name: row.get(2)?,
+        notes: row.get(3)?,
+        intensity: EncounterIntensity::from_str(&row.get::<_, String>(4)?),
+        damage_boost: row.get(5)?,
+        status: EncounterStatus::from_str(&row.get::<_, String>(6)?),
+        entity_ids: Vec::new(),
+        budget: calculate_budget(&[], &[], EncounterIntensity::Standard, false),
+        activated_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn active_party_levels(conn: &Connection, campaign_id: &str) -> AppResult<Vec<i32>> {
+    let mut stmt = conn.prepare("SELECT level FROM player_characters WHERE campaign_id = ?1 AND is_active = 1")?;
+    let levels = stmt
+        .query_map([campaign_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(levels)
+}
+
+fn encounter_entity_ids(conn: &Connection, encounter_id: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT ee.entity_id FROM encounter_entities ee
+         JOIN entities e ON e.id = ee.entity_id
+         WHERE ee.encounter_id = ?1
+         ORDER BY e.name COLLATE NOCASE",
+    )?;
+    let ids = stmt
+        .query_map([encounter_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+fn fill_encounter(conn: &Connection, mut encounter: Encounter, party_levels: &[i32]) -> AppResult<Encounter> {
+    encounter.entity_ids = encounter_entity_ids(conn, &encounter.id)?;
+    let entities = encounter
+        .entity_ids
+        .iter()
+        .map(|id| get_entity_by_id(conn, id))
+        .collect::<AppResult<Vec<_>>>()?;
+    encounter.budget = calculate_budget(party_levels, &entities, encounter.intensity, encounter.damage_boost);
+    Ok(encounter)
+}
+
+pub fn get_encounter_by_id(conn: &Connection, id: &str) -> AppResult<Encounter> {
+    let encounter = conn
+        .query_row(
+            &format!("SELECT {} FROM encounters WHERE id = ?1", SELECT_COLUMNS),
+            [id],
+            row_to_encounter,
+        )
+        .map_err(|_| AppError::EntityNotFound(format!("Encounter not found: {}", id)))?;
+
+    let party_levels = active_party_levels(conn, &encounter.campaign_id)?;
+    fill_encounter(conn, encounter, &party_levels)
+}
+
+fn get_encounters_for_campaign(conn: &Connection, campaign_id: &str) -> AppResult<Vec<Encounter>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM encounters WHERE campaign_id = ?1 ORDER BY created_at, rowid",
+        SELECT_COLUMNS
+    ))?;
+    let encounters = stmt
+        .query_map([campaign_id], row_to_encounter)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let party_levels = active_party_levels(conn, campaign_id)?;
+    encounters
+        .into_iter()
+        .map(|encounter| fill_encounter(conn, encounter, &party_levels))
+        .collect()
+}
+
+fn emit_encounters_update(app: &tauri::AppHandle, conn: &Connection, campaign_id: &str) -> AppResult<()> {
+    let encounters = get_encounters_for_campaign(conn, campaign_id)?;
+    app.emit("encounters-updated", EncountersPayload { encounters, campaign_id: campaign_id.to_string() })
+        .map_err(|e| AppError::EmitError(e.to_string()))
+}
+
+/// Put an entity in an encounter, moving it out of any other. Entities
+/// staged for later are hidden from players.
+fn add_entity_to_encounter(conn: &Connection, encounter: &Encounter, entity_id: &str) -> AppResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO encounter_entities (entity_id, encounter_id) VALUES (?1, ?2)",
+        params![entity_id, encounter.id],
+    )?;
+    if encounter.status == EncounterStatus::Staged {
+        conn.execute("UPDATE entities SET visible_to_players = 0 WHERE id = ?1", [entity_id])?;
+    }
+    Ok(())
+}
+
+fn touch_encounter(conn: &Connection, id: &str) -> AppResult<()> {
+    conn.execute("UPDATE encounters SET updated_at = datetime('now') WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// ============================================================================
+// CRUD Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn create_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    name: String,
+    notes: Option<String>,
+    intensity: Option<EncounterIntensity>,
+    damage_boost: Option<bool>,
+) -> AppResult<Encounter> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Encounter name cannot be empty".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        let id = Uuid::new_v4().to_string();
+
+        conn.execute(
+            "INSERT INTO encounters (id, campaign_id, name, notes, intensity, damage_boost) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                campaign_id,
+                name.trim(),
+                notes.filter(|n| !n.trim().is_empty()),
+                intensity.unwrap_or_default().as_str(),
+                damage_boost.unwrap_or(false)
+            ],
+        )?;
+
+        let encounter = get_encounter_by_id(conn, &id)?;
+        emit_encounters_update(&app, conn, &campaign_id)?;
+        Ok(encounter)
+    })
+}
+
+#[tauri::command]
+pub fn get_encounters(db: State<Database>) -> AppResult<Vec<Encounter>> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        get_encounters_for_campaign(conn, &campaign_id)
+    })
+}
+
+/// Change any of an encounter's name, notes and difficulty options. Blank
+/// notes clear them.
+#[tauri::command]
+pub fn update_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    name: Option<String>,
+    notes: Option<String>,
+    intensity: Option<EncounterIntensity>,
+    damage_boost: Option<bool>,
+) -> AppResult<Encounter> {
+    if name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::Validation("Encounter name cannot be empty".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let encounter = get_encounter_by_id(conn, &id)?;
+        let notes = match notes {
+            Some(notes) if notes.trim().is_empty() => None,
+            Some(notes) => Some(notes),
+            None => encounter.notes,
+        };
+
+        conn.execute(
+            "UPDATE encounters SET name = ?1, notes = ?2, intensity = ?3, damage_boost = ?4, updated_at = datetime('now') WHERE id = ?5",
+            params![
+                name.as_deref().map(str::trim).unwrap_or(&encounter.name),
+                notes,
+                intensity.unwrap_or(encounter.intensity).as_str(),
+                damage_boost.unwrap_or(encounter.damage_boost),
+                id
+            ],
+        )?;
+
+        let updated = get_encounter_by_id(conn, &id)?;
+        emit_encounters_update(&app, conn, &updated.campaign_id)?;
+        Ok(updated)
+    })
+}
+
+/// Delete an encounter. Its entities stay in the campaign unless
+/// `delete_entities` is set.
+#[tauri::command]
+pub fn delete_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    delete_entities: Option<bool>,
+) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let encounter = get_encounter_by_id(conn, &id)?;
+
+        let tx = conn.unchecked_transaction()?;
+        if delete_entities.unwrap_or(false) {
+            for entity_id in &encounter.entity_ids {
+                tx.execute("DELETE FROM entities WHERE id = ?1", [entity_id])?;
+            }
+        }
+        tx.execute("DELETE FROM encounters WHERE id = ?1", [&id])?;
+        tx.commit()?;
+
+        emit_encounters_update(&app, conn, &encounter.campaign_id)?;
+        if delete_entities.unwrap_or(false) {
+            emit_entities_update(&app, conn, &encounter.campaign_id)?;
+        }
+        Ok(())
+    })
+}
+
+// ============================================================================
+// Staging
+// ============================================================================
+
+#[tauri::command]
+pub fn add_entities_to_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    encounter_id: String,
+    entity_ids: Vec<String>,
+) -> AppResult<Encounter> {
+    db.with_conn(|conn| {
+        let encounter = get_encounter_by_id(conn, &encounter_id)?;
+
+        let tx = conn.unchecked_transaction()?;
+        for entity_id in &entity_ids {
+            let entity = get_entity_by_id(&tx, entity_id)?;
+            if entity.campaign_id != encounter.campaign_id {
+                return Err(AppError::Validation(format!("{} belongs to a different campaign", entity.name)));
+            }
+            add_entity_to_encounter(&tx, &encounter, entity_id)?;
+        }
+        touch_encounter(&tx, &encounter_id)?;
+        tx.commit()?;
+
+        let updated = get_encounter_by_id(conn, &encounter_id)?;
+        emit_encounters_update(&app, conn, &updated.campaign_id)?;
+        emit_entities_update(&app, conn, &updated.campaign_id)?;
+        Ok(updated)
+    })
+}
+
+/// Take an entity out of whichever encounter it's in, keeping it in the campaign
+#[tauri::command]
+pub fn remove_entity_from_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    entity_id: String,
+) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &entity_id)?;
+        conn.execute("DELETE FROM encounter_entities WHERE entity_id = ?1", [&entity_id])?;
+
+        emit_encounters_update(&app, conn, &entity.campaign_id)?;
+        Ok(())
+    })
+}
+
+/// Spawn instances of a library template straight into an encounter
+#[tauri::command]
+pub fn spawn_template_into_encounter(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    encounter_id: String,
+    template_id: String,
+    count: Option<u32>,
+) -> AppResult<Encounter> {
+    db.with_conn(|conn| {
+        let encounter = get_encounter_by_id(conn, &encounter_id)?;
+        let template = get_template_by_id(conn, &template_id)?;
+        if encounter.campaign_id != get_required_campaign_id(conn)? {
+            return Err(AppError::InvalidOperation("Encounter belongs to a different campaign".to_string()));
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let visible = encounter.status == EncounterStatus::Active;
+        for entity in spawn_template(&tx, &template, count.unwrap_or(1), visible)? {
+            add_entity_to_encounter(&tx, &encounter, &entity.id)?;
+        }
+        touch_encounter(&tx, &encounter_id)?;
+        tx.commit()?;
+
+        let updated = get_encounter_by_id(conn, &encounter_id)?;
+        emit_encounters_update(&app, conn, &updated.campaign_id)?;
+        emit_entities_update(&app, conn, &updated.campaign_id)?;
+        Ok(updated)
+    })
+}
+
+/// Start an encounter: reveal all its entities to players at once. Any
+/// other active encounter in the campaign is marked completed.
+#[tauri::command]
+pub fn activate_encounter(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<Encounter> {
+    db.with_conn(|conn| {
+        let encounter = get_encounter_by_id(conn, &id)?;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE encounters SET status = 'completed', updated_at = datetime('now')
+             WHERE campaign_id = ?1 AND status = 'active' AND id != ?2",
+            params![encounter.campaign_id, id],
+        )?;
+        tx.execute(
+            "UPDATE encounters SET status = 'active', activated_at = datetime('now'), updated_at = datetime('now') WHERE id = ?1",
+            [&id],
+        )?;
+        tx.execute(
+            "UPDATE entities SET visible_to_players = 1
+             WHERE id IN (SELECT entity_id FROM encounter_entities WHERE encounter_id = ?1)",
+            [&id],
+        )?;
+        tx.commit()?;
+
+        let updated = get_encounter_by_id(conn, &id)?;
+        emit_encounters_update(&app, conn, &updated.campaign_id)?;
+        emit_entities_update(&app, conn, &updated.campaign_id)?;
+        Ok(updated)
+    })
+}
+
+#[tauri::command]
+pub fn complete_encounter(db: State<Database>, app: tauri::AppHandle, id: String) -> AppResult<Encounter> {
+    db.with_conn(|conn| {
+        get_encounter_by_id(conn, &id)?;
+
+        conn.execute(
+            "UPDATE encounters SET status = 'completed', updated_at = datetime('now') WHERE id = ?1",
+            [&id],
+        )?;
+
+        let updated = get_encounter_by_id(conn, &id)?;
+        emit_encounters_update(&app, conn, &updated.campaign_id)?;
+        Ok(updated)
+    })
+}
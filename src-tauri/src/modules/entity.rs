@@ -3,8 +3,11 @@ use serde::{Deserialize, Serialize};
 use tauri::{Emitter, State};
 use uuid::Uuid;
 
-use super::campaign::get_current_campaign_id;
+use super::campaign::{get_campaign_by_id, get_current_campaign_id};
+use super::adversary_library::next_instance_name;
+use super::condition::{get_conditions_by_target, get_conditions_for_target, Condition, ConditionTarget};
 use super::database::Database;
+use super::dice_engine;
 use super::error::{AppError, AppResult};
 
 // ============================================================================
@@ -24,6 +27,31 @@ pub struct Entity {
     pub visible_to_players: bool,
     #[serde(default)]
     pub entity_type: EntityType,
+    /// Minions sharing a group take each other's spillover damage
+    #[serde(default)]
+    pub group_id: Option<String>,
+    /// Armor Slots marked out of `armor_slots`
+    #[serde(default)]
+    pub armor_marked: i32,
+    #[serde(flatten)]
+    pub stat_block: AdversaryStatBlock,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+}
+
+impl Entity {
+    /// Damage notation for the standard attack: a Horde that has marked at
+    /// least half its HP deals its reduced `horde_damage` instead
+    pub fn standard_attack_damage(&self) -> Option<&str> {
+        let attack = self.stat_block.attack.as_ref()?;
+        let weakened = self.stat_block.role == AdversaryRole::Horde
+            && self.hp_max - self.hp_current >= (self.hp_max + 1) / 2;
+
+        match (&self.stat_block.horde_damage, weakened) {
+            (Some(horde_damage), true) => Some(horde_damage),
+            _ => Some(&attack.damage),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
@@ -35,14 +63,14 @@ pub enum EntityType {
 }
 
 impl EntityType {
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             EntityType::Npc => "npc",
             EntityType::Adversary => "adversary",
         }
     }
 
-    fn from_str(s: &str) -> Self {
+    pub fn from_str(s: &str) -> Self {
         match s {
             "npc" => EntityType::Npc,
             _ => EntityType::Adversary,
@@ -50,6 +78,250 @@ impl EntityType {
     }
 }
 
+/// Adversary role from the Daggerheart stat block
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AdversaryRole {
+    Bruiser,
+    Horde,
+    Leader,
+    Minion,
+    Ranged,
+    Skulk,
+    Social,
+    Solo,
+    #[default]
+    Standard,
+    Support,
+}
+
+impl AdversaryRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdversaryRole::Bruiser => "bruiser",
+            AdversaryRole::Horde => "horde",
+            AdversaryRole::Leader => "leader",
+            AdversaryRole::Minion => "minion",
+            AdversaryRole::Ranged => "ranged",
+            AdversaryRole::Skulk => "skulk",
+            AdversaryRole::Social => "social",
+            AdversaryRole::Solo => "solo",
+            AdversaryRole::Standard => "standard",
+            AdversaryRole::Support => "support",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "bruiser" => AdversaryRole::Bruiser,
+            "horde" => AdversaryRole::Horde,
+            "leader" => AdversaryRole::Leader,
+            "minion" => AdversaryRole::Minion,
+            "ranged" => AdversaryRole::Ranged,
+            "skulk" => AdversaryRole::Skulk,
+            "social" => AdversaryRole::Social,
+            "solo" => AdversaryRole::Solo,
+            "support" => AdversaryRole::Support,
+            _ => AdversaryRole::Standard,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackRange {
+    #[default]
+    Melee,
+    VeryClose,
+    Close,
+    Far,
+    VeryFar,
+}
+
+impl AttackRange {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AttackRange::Melee => "melee",
+            AttackRange::VeryClose => "very_close",
+            AttackRange::Close => "close",
+            AttackRange::Far => "far",
+            AttackRange::VeryFar => "very_far",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "very_close" => AttackRange::VeryClose,
+            "close" => AttackRange::Close,
+            "far" => AttackRange::Far,
+            "very_far" => AttackRange::VeryFar,
+            _ => AttackRange::Melee,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Magic,
+}
+
+impl DamageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DamageType::Physical => "physical",
+            DamageType::Magic => "magic",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "magic" => DamageType::Magic,
+            _ => DamageType::Physical,
+        }
+    }
+}
+
+/// How an entity takes one type of damage
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageAffinity {
+    #[default]
+    Normal,
+    /// Half damage
+    Resistant,
+    /// No damage
+    Immune,
+    /// Double damage
+    Vulnerable,
+}
+
+impl DamageAffinity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DamageAffinity::Normal => "normal",
+            DamageAffinity::Resistant => "resistant",
+            DamageAffinity::Immune => "immune",
+            DamageAffinity::Vulnerable => "vulnerable",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "resistant" => DamageAffinity::Resistant,
+            "immune" => DamageAffinity::Immune,
+            "vulnerable" => DamageAffinity::Vulnerable,
+            _ => DamageAffinity::Normal,
+        }
+    }
+}
+
+/// The adversary's standard attack, e.g. "Claws, Melee, 1d8+3 phy"
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StandardAttack {
+    pub name: String,
+    #[serde(default)]
+    pub range: AttackRange,
+    /// Damage dice notation
+    pub damage: String,
+    #[serde(default)]
+    pub damage_type: DamageType,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct EntityExperience {
+    pub name: String,
+    pub modifier: i32,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FeatureKind {
+    #[default]
+    Passive,
+    Action,
+    Reaction,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AdversaryFeature {
+    pub name: String,
+    #[serde(default)]
+    pub kind: FeatureKind,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Daggerheart stat block fields; flattened into `Entity`
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AdversaryStatBlock {
+    #[serde(default = "default_tier")]
+    pub tier: i32,
+    #[serde(default)]
+    pub role: AdversaryRole,
+    /// Target number for attacks against this entity
+    #[serde(default)]
+    pub difficulty: Option<i32>,
+    #[serde(default)]
+    pub attack_modifier: i32,
+    #[serde(default)]
+    pub attack: Option<StandardAttack>,
+    #[serde(default)]
+    pub motives_and_tactics: Option<String>,
+    #[serde(default)]
+    pub experiences: Vec<EntityExperience>,
+    #[serde(default)]
+    pub features: Vec<AdversaryFeature>,
+    /// The X in a Minion's "Minion (X)": every X damage defeats another
+    /// Minion in its group
+    #[serde(default)]
+    pub minion_threshold: Option<i32>,
+    /// Damage notation a Horde's standard attack deals once it has marked
+    /// at least half its HP
+    #[serde(default)]
+    pub horde_damage: Option<String>,
+    #[serde(default)]
+    pub physical_affinity: DamageAffinity,
+    #[serde(default)]
+    pub magic_affinity: DamageAffinity,
+    /// Armor Slots that can each be marked to reduce incoming damage by one threshold
+    #[serde(default)]
+    pub armor_slots: i32,
+}
+
+impl AdversaryStatBlock {
+    pub fn affinity(&self, damage_type: DamageType) -> DamageAffinity {
+        match damage_type {
+            DamageType::Physical => self.physical_affinity,
+            DamageType::Magic => self.magic_affinity,
+        }
+    }
+}
+
+fn default_tier() -> i32 { 1 }
+
+impl Default for AdversaryStatBlock {
+    fn default() -> Self {
+        AdversaryStatBlock {
+            tier: default_tier(),
+            role: AdversaryRole::default(),
+            difficulty: None,
+            attack_modifier: 0,
+            attack: None,
+            motives_and_tactics: None,
+            experiences: Vec::new(),
+            features: Vec::new(),
+            minion_threshold: None,
+            horde_damage: None,
+            physical_affinity: DamageAffinity::Normal,
+            magic_affinity: DamageAffinity::Normal,
+            armor_slots: 0,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct DamageThresholds {
     pub minor: i32,
@@ -66,8 +338,141 @@ struct EntitiesPayload {
 #[derive(Clone, Serialize)]
 pub struct DamageResult {
     pub entity: Entity,
+    /// HP marked
     pub damage_dealt: i32,
     pub threshold_hit: Option<String>,
+    /// Every entity this damage took to 0 HP, the target first, then any
+    /// Minions from its group taken out by spillover
+    pub defeated: Vec<Entity>,
+    pub damage_type: DamageType,
+    /// Damage after resistance, immunity or vulnerability
+    pub damage_taken: i32,
+    pub armor_marked: i32,
+    /// What happened at each step, in order, for the GM to narrate
+    pub steps: Vec<String>,
+}
+
+/// What changed an entity's HP or Stress
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityEventKind {
+    Damage,
+    HpChange,
+    HpSet,
+    Stress,
+}
+
+impl EntityEventKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EntityEventKind::Damage => "damage",
+            EntityEventKind::HpChange => "hp_change",
+            EntityEventKind::HpSet => "hp_set",
+            EntityEventKind::Stress => "stress",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "damage" => EntityEventKind::Damage,
+            "hp_set" => EntityEventKind::HpSet,
+            "stress" => EntityEventKind::Stress,
+            _ => EntityEventKind::HpChange,
+        }
+    }
+}
+
+/// Where a change came from
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    Manual,
+    Attack,
+    /// A command applied to a selection of entities
+    Bulk,
+    MinionSpillover,
+    /// A condition's per-spotlight effect
+    Condition,
+}
+
+impl EventSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EventSource::Manual => "manual",
+            EventSource::Attack => "attack",
+            EventSource::Bulk => "bulk",
+            EventSource::MinionSpillover => "minion_spillover",
+            EventSource::Condition => "condition",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "attack" => EventSource::Attack,
+            "bulk" => EventSource::Bulk,
+            "minion_spillover" => EventSource::MinionSpillover,
+            "condition" => EventSource::Condition,
+            _ => EventSource::Manual,
+        }
+    }
+}
+
+/// One recorded HP or Stress change, with the values before and after
+#[derive(Clone, Serialize, Debug)]
+pub struct EntityEvent {
+    pub id: String,
+    pub entity_id: String,
+    pub campaign_id: String,
+    pub kind: EntityEventKind,
+    pub source: EventSource,
+    /// Damage before defenses, the HP or Stress delta, or the HP set
+    pub raw_amount: i32,
+    pub damage_type: Option<DamageType>,
+    pub threshold_hit: Option<String>,
+    pub hp_before: i32,
+    pub hp_after: i32,
+    pub stress_before: i32,
+    pub stress_after: i32,
+    pub armor_before: i32,
+    pub armor_after: i32,
+    pub undone: bool,
+    pub created_at: String,
+    /// For Minion spillover, the hit on another Minion that caused it
+    pub parent_event_id: Option<String>,
+}
+
+/// What to record about a change besides the entity's before and after
+pub struct EventDetails {
+    pub kind: EntityEventKind,
+    pub source: EventSource,
+    pub raw_amount: i32,
+    pub damage_type: Option<DamageType>,
+    pub threshold_hit: Option<String>,
+    pub parent_event_id: Option<String>,
+}
+
+impl EventDetails {
+    pub fn new(kind: EntityEventKind, source: EventSource, raw_amount: i32) -> Self {
+        EventDetails {
+            kind,
+            source,
+            raw_amount,
+            damage_type: None,
+            threshold_hit: None,
+            parent_event_id: None,
+        }
+    }
+}
+
+/// Incoming damage before the target's defenses
+#[derive(Clone, Copy, Debug, Deserialize, Default)]
+pub struct DamageInput {
+    pub amount: i32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Direct damage can't be reduced by marking Armor Slots
+    #[serde(default)]
+    pub direct: bool,
 }
 
 // ============================================================================
@@ -90,40 +495,197 @@ fn row_to_entity(row: &Row) -> rusqlite::Result<Entity> {
         },
         visible_to_players: row.get::<_, i32>(10)? != 0,
         entity_type: EntityType::from_str(&row.get::<_, String>(11)?),
+        group_id: row.get(12)?,
+        armor_marked: row.get(13)?,
+        stat_block: row_to_stat_block(row, 14)?,
+        conditions: Vec::new(),
     })
 }
 
-const SELECT_COLUMNS: &str = "id, campaign_id, name, hp_current, hp_max, stress_current, stress_max, threshold_minor, threshold_major, threshold_severe, visible_to_players, entity_type";
+/// Read the stat block columns (`STAT_BLOCK_COLUMNS`) starting at `offset`
+pub fn row_to_stat_block(row: &Row, offset: usize) -> rusqlite::Result<AdversaryStatBlock> {
+    Ok(AdversaryStatBlock {
+        difficulty: row.get(offset)?,
+        tier: row.get(offset + 1)?,
+        role: AdversaryRole::from_str(&row.get::<_, String>(offset + 2)?),
+        attack_modifier: row.get(offset + 3)?,
+        attack: match (row.get::<_, Option<String>>(offset + 4)?, row.get::<_, Option<String>>(offset + 6)?) {
+            (Some(name), Some(damage)) => Some(StandardAttack {
+                name,
+                range: AttackRange::from_str(&row.get::<_, Option<String>>(offset + 5)?.unwrap_or_default()),
+                damage,
+                damage_type: DamageType::from_str(&row.get::<_, Option<String>>(offset + 15)?.unwrap_or_default()),
+            }),
+            _ => None,
+        },
+        motives_and_tactics: row.get(offset + 7)?,
+        experiences: serde_json::from_str(&row.get::<_, String>(offset + 8)?).unwrap_or_default(),
+        features: serde_json::from_str(&row.get::<_, String>(offset + 9)?).unwrap_or_default(),
+        minion_threshold: row.get(offset + 10)?,
+        horde_damage: row.get(offset + 11)?,
+        physical_affinity: DamageAffinity::from_str(&row.get::<_, String>(offset + 12)?),
+        magic_affinity: DamageAffinity::from_str(&row.get::<_, String>(offset + 13)?),
+        armor_slots: row.get(offset + 14)?,
+    })
+}
 
-fn get_entities_for_campaign(conn: &Connection, campaign_id: &str) -> AppResult<Vec<Entity>> {
-    let mut stmt = conn.prepare(
-        &format!("SELECT {} FROM entities WHERE campaign_id = ?1", SELECT_COLUMNS)
-    )?;
+/// Stat block columns, shared by entities and adversary templates
+pub const STAT_BLOCK_COLUMNS: &str = "difficulty, tier, role, attack_modifier, attack_name, attack_range, attack_damage, motives_and_tactics, experiences, features, minion_threshold, horde_damage, physical_affinity, magic_affinity, armor_slots, attack_damage_type";
+
+const BASE_COLUMNS: &str = "id, campaign_id, name, hp_current, hp_max, stress_current, stress_max, threshold_minor, threshold_major, threshold_severe, visible_to_players, entity_type, group_id, armor_marked";
 
-    let entities = stmt
+fn select_columns() -> String {
+    format!("{}, {}", BASE_COLUMNS, STAT_BLOCK_COLUMNS)
+}
+
+/// A campaign's entities; `visible_only` leaves out everything hidden from players
+fn get_entities_for_campaign(conn: &Connection, campaign_id: &str, visible_only: bool) -> AppResult<Vec<Entity>> {
+    let query = if visible_only {
+        format!("SELECT {} FROM entities WHERE campaign_id = ?1 AND visible_to_players = 1", select_columns())
+    } else {
+        format!("SELECT {} FROM entities WHERE campaign_id = ?1", select_columns())
+    };
+
+    let mut stmt = conn.prepare(&query)?;
+
+    let mut entities = stmt
         .query_map([campaign_id], |row| row_to_entity(row))?
         .collect::<Result<Vec<_>, _>>()?;
 
+    attach_conditions(conn, campaign_id, &mut entities, visible_only)?;
     Ok(entities)
 }
 
-fn get_entity_by_id(conn: &Connection, id: &str) -> AppResult<Entity> {
+/// Fill in each entity's conditions, leaving out hidden ones for the player view
+fn attach_conditions(conn: &Connection, campaign_id: &str, entities: &mut [Entity], visible_only: bool) -> AppResult<()> {
+    let mut conditions = get_conditions_by_target(conn, campaign_id, ConditionTarget::Entity)?;
+    for entity in entities {
+        let mut entity_conditions = conditions.remove(&entity.id).unwrap_or_default();
+        if visible_only {
+            entity_conditions.retain(|c| c.visible_to_players);
+        }
+        entity.conditions = entity_conditions;
+    }
+    Ok(())
+}
+
+pub fn get_entity_by_id(conn: &Connection, id: &str) -> AppResult<Entity> {
     let mut stmt = conn.prepare(
-        &format!("SELECT {} FROM entities WHERE id = ?1", SELECT_COLUMNS)
+        &format!("SELECT {} FROM entities WHERE id = ?1", select_columns())
     )?;
 
-    stmt.query_row([id], |row| row_to_entity(row))
-        .map_err(|_| AppError::EntityNotFound(id.to_string()))
+    let mut entity = stmt.query_row([id], |row| row_to_entity(row))
+        .map_err(|_| AppError::EntityNotFound(id.to_string()))?;
+
+    entity.conditions = get_conditions_for_target(conn, ConditionTarget::Entity, id)?;
+    Ok(entity)
 }
 
-fn get_required_campaign_id(conn: &Connection) -> AppResult<String> {
+pub fn validate_stat_block(stat_block: &AdversaryStatBlock) -> AppResult<()> {
+    if !(1..=4).contains(&stat_block.tier) {
+        return Err(AppError::Validation("Tier must be between 1 and 4".to_string()));
+    }
+    if stat_block.difficulty.is_some_and(|d| d < 1) {
+        return Err(AppError::Validation("Difficulty must be at least 1".to_string()));
+    }
+    if let Some(attack) = &stat_block.attack {
+        if attack.name.trim().is_empty() {
+            return Err(AppError::Validation("Standard attack needs a name".to_string()));
+        }
+        dice_engine::parse_notation(&attack.damage)?;
+    }
+    if stat_block.experiences.iter().any(|e| e.name.trim().is_empty()) {
+        return Err(AppError::Validation("Experiences need a name".to_string()));
+    }
+    if stat_block.features.iter().any(|f| f.name.trim().is_empty()) {
+        return Err(AppError::Validation("Features need a name".to_string()));
+    }
+    if stat_block.minion_threshold.is_some_and(|x| x < 1) {
+        return Err(AppError::Validation("Minion threshold must be at least 1".to_string()));
+    }
+    if let Some(damage) = &stat_block.horde_damage {
+        dice_engine::parse_notation(damage)?;
+    }
+    if stat_block.armor_slots < 0 {
+        return Err(AppError::Validation("Armor Slots cannot be negative".to_string()));
+    }
+    Ok(())
+}
+
+/// Bind values for `STAT_BLOCK_COLUMNS`, in order
+pub fn stat_block_params(stat_block: &AdversaryStatBlock) -> AppResult<Vec<Box<dyn rusqlite::ToSql>>> {
+    let attack = stat_block.attack.as_ref();
+    let experiences = serde_json::to_string(&stat_block.experiences)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+    let features = serde_json::to_string(&stat_block.features)
+        .map_err(|e| AppError::PersistenceError(e.to_string()))?;
+
+    Ok(vec![
+        Box::new(stat_block.difficulty),
+        Box::new(stat_block.tier),
+        Box::new(stat_block.role.as_str()),
+        Box::new(stat_block.attack_modifier),
+        Box::new(attack.map(|a| a.name.clone())),
+        Box::new(attack.map(|a| a.range.as_str())),
+        Box::new(attack.map(|a| a.damage.clone())),
+        Box::new(stat_block.motives_and_tactics.clone()),
+        Box::new(experiences),
+        Box::new(features),
+        Box::new(stat_block.minion_threshold),
+        Box::new(stat_block.horde_damage.clone()),
+        Box::new(stat_block.physical_affinity.as_str()),
+        Box::new(stat_block.magic_affinity.as_str()),
+        Box::new(stat_block.armor_slots),
+        Box::new(attack.map(|a| a.damage_type.as_str())),
+    ])
+}
+
+/// Insert a fully built entity without emitting
+pub fn insert_entity(conn: &Connection, entity: &Entity) -> AppResult<()> {
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(entity.id.clone()),
+        Box::new(entity.campaign_id.clone()),
+        Box::new(entity.name.clone()),
+        Box::new(entity.hp_current),
+        Box::new(entity.hp_max),
+        Box::new(entity.stress_current),
+        Box::new(entity.stress_max),
+        Box::new(entity.thresholds.minor),
+        Box::new(entity.thresholds.major),
+        Box::new(entity.thresholds.severe),
+        Box::new(entity.visible_to_players as i32),
+        Box::new(entity.entity_type.as_str()),
+        Box::new(entity.group_id.clone()),
+        Box::new(entity.armor_marked),
+    ];
+    values.extend(stat_block_params(&entity.stat_block)?);
+
+    conn.execute(
+        &format!(
+            "INSERT INTO entities ({}) VALUES ({})",
+            select_columns(),
+            (1..=values.len()).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ")
+        ),
+        rusqlite::params_from_iter(values.iter()),
+    )?;
+
+    Ok(())
+}
+
+pub fn get_required_campaign_id(conn: &Connection) -> AppResult<String> {
     get_current_campaign_id(conn)?
         .ok_or_else(|| AppError::InvalidOperation("No campaign selected".to_string()))
 }
 
-fn emit_entities_update(app: &tauri::AppHandle, conn: &Connection, campaign_id: &str) -> AppResult<()> {
-    let entities = get_entities_for_campaign(conn, campaign_id)?;
+/// Emit `entities-updated` for the GM windows and `player-entities-updated`
+/// for the player view, which carries only what players may see
+pub fn emit_entities_update(app: &tauri::AppHandle, conn: &Connection, campaign_id: &str) -> AppResult<()> {
+    let entities = get_entities_for_campaign(conn, campaign_id, false)?;
     app.emit("entities-updated", EntitiesPayload { entities, campaign_id: campaign_id.to_string() })
+        .map_err(|e| AppError::EmitError(e.to_string()))?;
+
+    let entities = get_entities_for_campaign(conn, campaign_id, true)?;
+    app.emit("player-entities-updated", EntitiesPayload { entities, campaign_id: campaign_id.to_string() })
         .map_err(|e| AppError::EmitError(e.to_string()))
 }
 
@@ -140,32 +702,16 @@ pub fn create_entity(
     stress_max: Option<i32>,
     thresholds: DamageThresholds,
     entity_type: EntityType,
+    stat_block: Option<AdversaryStatBlock>,
 ) -> AppResult<Entity> {
     let id = Uuid::new_v4().to_string();
-    let stress_max = stress_max.unwrap_or(0).min(12); // Cap at 12
+    let stress_max = stress_max.unwrap_or(0).min(DEFAULT_STRESS_CAP);
+    let stat_block = stat_block.unwrap_or_default();
+    validate_stat_block(&stat_block)?;
 
     db.with_conn(|conn| {
         let campaign_id = get_required_campaign_id(conn)?;
 
-        conn.execute(
-            "INSERT INTO entities (id, campaign_id, name, hp_current, hp_max, stress_current, stress_max, threshold_minor, threshold_major, threshold_severe, visible_to_players, entity_type)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            params![
-                id,
-                campaign_id,
-                name,
-                hp_max,
-                hp_max,
-                0, // stress_current starts at 0
-                stress_max,
-                thresholds.minor,
-                thresholds.major,
-                thresholds.severe,
-                0,
-                entity_type.as_str()
-            ],
-        )?;
-
         let entity = Entity {
             id,
             campaign_id: campaign_id.clone(),
@@ -177,7 +723,12 @@ pub fn create_entity(
             thresholds,
             visible_to_players: false,
             entity_type,
+            group_id: None,
+            armor_marked: 0,
+            stat_block,
+            conditions: Vec::new(),
         };
+        insert_entity(conn, &entity)?;
 
         emit_entities_update(&app, conn, &campaign_id)?;
         Ok(entity)
@@ -205,20 +756,7 @@ pub fn delete_entity(db: State<Database>, app: tauri::AppHandle, id: String) ->
 pub fn get_entities(db: State<Database>, visible_only: bool) -> AppResult<Vec<Entity>> {
     db.with_conn(|conn| {
         let campaign_id = get_required_campaign_id(conn)?;
-
-        let query = if visible_only {
-            format!("SELECT {} FROM entities WHERE campaign_id = ?1 AND visible_to_players = 1", SELECT_COLUMNS)
-        } else {
-            format!("SELECT {} FROM entities WHERE campaign_id = ?1", SELECT_COLUMNS)
-        };
-
-        let mut stmt = conn.prepare(&query)?;
-
-        let entities = stmt
-            .query_map([&campaign_id], |row| row_to_entity(row))?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(entities)
+        get_entities_for_campaign(conn, &campaign_id, visible_only)
     })
 }
 
@@ -226,6 +764,24 @@ pub fn get_entities(db: State<Database>, visible_only: bool) -> AppResult<Vec<En
 // HP Management
 // ============================================================================
 
+/// Heal (positive) or mark (negative) HP on an entity without emitting,
+/// clamped to 0..hp_max, and record it in the entity's history
+pub fn apply_entity_hp_change(conn: &Connection, entity: Entity, amount: i32, source: EventSource) -> AppResult<Entity> {
+    let new_hp = (entity.hp_current + amount).clamp(0, entity.hp_max);
+
+    conn.execute(
+        "UPDATE entities SET hp_current = ?1 WHERE id = ?2",
+        params![new_hp, entity.id],
+    )?;
+
+    let updated_entity = Entity {
+        hp_current: new_hp,
+        ..entity.clone()
+    };
+    record_entity_event(conn, &entity, &updated_entity, &EventDetails::new(EntityEventKind::HpChange, source, amount))?;
+    Ok(updated_entity)
+}
+
 #[tauri::command]
 pub fn update_entity_hp(
     db: State<Database>,
@@ -235,17 +791,7 @@ pub fn update_entity_hp(
 ) -> AppResult<Entity> {
     db.with_conn(|conn| {
         let entity = get_entity_by_id(conn, &id)?;
-        let new_hp = (entity.hp_current + amount).clamp(0, entity.hp_max);
-
-        conn.execute(
-            "UPDATE entities SET hp_current = ?1 WHERE id = ?2",
-            params![new_hp, id],
-        )?;
-
-        let updated_entity = Entity {
-            hp_current: new_hp,
-            ..entity
-        };
+        let updated_entity = apply_entity_hp_change(conn, entity, amount, EventSource::Manual)?;
 
         emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
         Ok(updated_entity)
@@ -270,57 +816,270 @@ pub fn set_entity_hp(
 
         let updated_entity = Entity {
             hp_current: new_hp,
-            ..entity
+            ..entity.clone()
         };
+        record_entity_event(
+            conn,
+            &entity,
+            &updated_entity,
+            &EventDetails::new(EntityEventKind::HpSet, EventSource::Manual, value),
+        )?;
 
         emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
         Ok(updated_entity)
     })
 }
 
+/// Resolve damage against an entity without emitting: damage type affinity,
+/// Minion spillover, thresholds (massive damage only when the campaign allows
+/// it), then an Armor Slot for non-direct damage. Shared by `apply_damage`
+/// and attack resolution, and recorded in the entity's history.
+pub fn apply_damage_to_entity(
+    conn: &Connection,
+    entity: Entity,
+    input: DamageInput,
+    source: EventSource,
+) -> AppResult<DamageResult> {
+    let before = entity.clone();
+    // Spillover events point at this hit, so its id is needed up front
+    let event_id = Uuid::new_v4().to_string();
+    let result = resolve_damage(conn, entity, input, &event_id)?;
+
+    let details = EventDetails {
+        damage_type: Some(input.damage_type),
+        threshold_hit: result.threshold_hit.clone(),
+        ..EventDetails::new(EntityEventKind::Damage, source, input.amount)
+    };
+    insert_entity_event(conn, &event_id, &before, &result.entity, &details)?;
+    Ok(result)
+}
+
+fn resolve_damage(conn: &Connection, entity: Entity, input: DamageInput, event_id: &str) -> AppResult<DamageResult> {
+    let campaign = get_campaign_by_id(conn, &entity.campaign_id)?;
+    let mut steps = vec![format!("{} takes {} {} damage", entity.name, input.amount.max(0), input.damage_type.as_str())];
+
+    let damage = match entity.stat_block.affinity(input.damage_type) {
+        DamageAffinity::Normal => input.amount.max(0),
+        DamageAffinity::Resistant => {
+            let halved = input.amount.max(0) / 2;
+            steps.push(format!("Resistant to {} damage: halved to {}", input.damage_type.as_str(), halved));
+            halved
+        }
+        DamageAffinity::Immune => {
+            steps.push(format!("Immune to {} damage: no damage", input.damage_type.as_str()));
+            0
+        }
+        DamageAffinity::Vulnerable => {
+            let doubled = input.amount.max(0).saturating_mul(2);
+            steps.push(format!("Vulnerable to {} damage: doubled to {}", input.damage_type.as_str(), doubled));
+            doubled
+        }
+    };
+
+    let mut result = DamageResult {
+        entity: entity.clone(),
+        damage_dealt: 0,
+        threshold_hit: None,
+        defeated: Vec::new(),
+        damage_type: input.damage_type,
+        damage_taken: damage,
+        armor_marked: 0,
+        steps,
+    };
+
+    if entity.hp_current == 0 {
+        result.steps.push(format!("{} is already defeated", entity.name));
+        return Ok(result);
+    }
+
+    if entity.stat_block.role == AdversaryRole::Minion {
+        return apply_minion_damage(conn, entity, damage, event_id, result);
+    }
+
+    let massive_threshold = entity.thresholds.severe * 2;
+    let (threshold_hit, mut hp_loss) = if damage >= massive_threshold && campaign.allow_massive_damage {
+        (Some("massive".to_string()), 4)
+    } else if damage >= entity.thresholds.severe {
+        (Some("severe".to_string()), 3)
+    } else if damage >= entity.thresholds.major {
+        (Some("major".to_string()), 2)
+    } else if damage >= entity.thresholds.minor {
+        (Some("minor".to_string()), 1)
+    } else {
+        (None, 0)
+    };
+
+    match &threshold_hit {
+        Some(threshold) if threshold == "severe" && damage >= massive_threshold => result.steps.push(format!(
+            "{} meets the massive threshold ({}), but massive damage is off: Severe, mark 3 HP",
+            damage, massive_threshold
+        )),
+        Some(threshold) => result.steps.push(format!("{} meets the {} threshold: mark {} HP", damage, threshold, hp_loss)),
+        None => result.steps.push(format!("{} is below the Minor threshold ({}): no HP marked", damage, entity.thresholds.minor)),
+    }
+
+    let mut armor_marked = entity.armor_marked;
+    if hp_loss > 0 && entity.armor_marked < entity.stat_block.armor_slots {
+        if input.direct {
+            result.steps.push("Direct damage: Armor can't reduce it".to_string());
+        } else {
+            armor_marked += 1;
+            hp_loss -= 1;
+            result.armor_marked = 1;
+            result.steps.push(format!(
+                "Marks an Armor Slot ({} of {} left): HP marked reduced to {}",
+                entity.stat_block.armor_slots - armor_marked,
+                entity.stat_block.armor_slots,
+                hp_loss
+            ));
+        }
+    }
+
+    let actual_hp_loss = hp_loss.min(entity.hp_current);
+    let new_hp = entity.hp_current - actual_hp_loss;
+
+    conn.execute(
+        "UPDATE entities SET hp_current = ?1, armor_marked = ?2 WHERE id = ?3",
+        params![new_hp, armor_marked, entity.id],
+    )?;
+
+    if actual_hp_loss > 0 {
+        result.steps.push(format!("HP {} → {}", entity.hp_current, new_hp));
+    }
+
+    let updated_entity = Entity {
+        hp_current: new_hp,
+        armor_marked,
+        ..entity
+    };
+    if actual_hp_loss > 0 && new_hp == 0 {
+        result.steps.push(format!("{} is defeated", updated_entity.name));
+        result.defeated.push(updated_entity.clone());
+    }
+
+    result.entity = updated_entity;
+    result.damage_dealt = actual_hp_loss;
+    result.threshold_hit = threshold_hit;
+    Ok(result)
+}
+
+/// Minions are defeated by any damage. With "Minion (X)", every X damage
+/// also defeats another standing Minion from the same group.
+fn apply_minion_damage(
+    conn: &Connection,
+    entity: Entity,
+    damage: i32,
+    event_id: &str,
+    mut result: DamageResult,
+) -> AppResult<DamageResult> {
+    if damage <= 0 {
+        result.steps.push("Minion takes no damage".to_string());
+        return Ok(result);
+    }
+
+    let damage_dealt = entity.hp_current;
+    conn.execute("UPDATE entities SET hp_current = 0 WHERE id = ?1", [&entity.id])?;
+    let updated_entity = Entity { hp_current: 0, ..entity };
+    result.steps.push(format!("Minion: any damage defeats {}", updated_entity.name));
+    result.defeated.push(updated_entity.clone());
+
+    if let (Some(x), Some(group_id)) = (updated_entity.stat_block.minion_threshold, &updated_entity.group_id) {
+        let extra = damage / x;
+        if extra > 0 {
+            // Oldest first, the order the GM sees them spawned in
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM entities
+                 WHERE group_id = ?1 AND id != ?2 AND hp_current > 0
+                 ORDER BY rowid
+                 LIMIT ?3",
+                select_columns()
+            ))?;
+            let others = stmt
+                .query_map(params![group_id, updated_entity.id, extra], row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !others.is_empty() {
+                result.steps.push(format!(
+                    "Minion ({}): {} damage defeats {} more: {}",
+                    x,
+                    damage,
+                    others.len(),
+                    others.iter().map(|o| o.name.as_str()).collect::<Vec<_>>().join(", ")
+                ));
+            }
+            for other in others {
+                conn.execute("UPDATE entities SET hp_current = 0 WHERE id = ?1", [&other.id])?;
+                let defeated = Entity { hp_current: 0, ..other.clone() };
+                record_entity_event(
+                    conn,
+                    &other,
+                    &defeated,
+                    &EventDetails {
+                        parent_event_id: Some(event_id.to_string()),
+                        ..EventDetails::new(EntityEventKind::Damage, EventSource::MinionSpillover, damage)
+                    },
+                )?;
+                result.defeated.push(defeated);
+            }
+        }
+    }
+
+    result.entity = updated_entity;
+    result.damage_dealt = damage_dealt;
+    Ok(result)
+}
+
+/// Deal damage to an entity. `damage_type` defaults to physical; `direct`
+/// damage can't be reduced with Armor Slots.
 #[tauri::command]
 pub fn apply_damage(
     db: State<Database>,
     app: tauri::AppHandle,
     id: String,
     damage: i32,
+    damage_type: Option<DamageType>,
+    direct: Option<bool>,
 ) -> AppResult<DamageResult> {
+    let input = DamageInput {
+        amount: damage,
+        damage_type: damage_type.unwrap_or_default(),
+        direct: direct.unwrap_or(false),
+    };
+
     db.with_conn(|conn| {
         let entity = get_entity_by_id(conn, &id)?;
 
-        let massive_threshold = entity.thresholds.severe * 2;
-        let (threshold_hit, hp_loss) = if damage >= massive_threshold {
-            (Some("massive".to_string()), 4)
-        } else if damage >= entity.thresholds.severe {
-            (Some("severe".to_string()), 3)
-        } else if damage >= entity.thresholds.major {
-            (Some("major".to_string()), 2)
-        } else if damage >= entity.thresholds.minor {
-            (Some("minor".to_string()), 1)
-        } else {
-            (None, 0)
-        };
+        // Minion spillover and armor can touch several rows
+        let tx = conn.unchecked_transaction()?;
+        let result = apply_damage_to_entity(&tx, entity, input, EventSource::Manual)?;
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &result.entity.campaign_id)?;
+        Ok(result)
+    })
+}
 
-        let actual_hp_loss = hp_loss.min(entity.hp_current);
-        let new_hp = entity.hp_current - actual_hp_loss;
+/// Clear marked Armor Slots (positive `amount`) or mark more (negative)
+#[tauri::command]
+pub fn repair_entity_armor(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    amount: i32,
+) -> AppResult<Entity> {
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &id)?;
+        let armor_marked = (entity.armor_marked - amount).clamp(0, entity.stat_block.armor_slots.max(0));
 
         conn.execute(
-            "UPDATE entities SET hp_current = ?1 WHERE id = ?2",
-            params![new_hp, id],
+            "UPDATE entities SET armor_marked = ?1 WHERE id = ?2",
+            params![armor_marked, id],
         )?;
 
-        let updated_entity = Entity {
-            hp_current: new_hp,
-            ..entity
-        };
+        let updated_entity = Entity { armor_marked, ..entity };
 
         emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
-
-        Ok(DamageResult {
-            entity: updated_entity,
-            damage_dealt: actual_hp_loss,
-            threshold_hit,
-        })
+        Ok(updated_entity)
     })
 }
 
@@ -339,6 +1098,61 @@ pub struct StressResult {
 /// Default stress cap when no specific maximum is set
 const DEFAULT_STRESS_CAP: i32 = 12;
 
+/// Mark (positive) or clear (negative) Stress on an entity without emitting.
+/// Stress past the cap overflows into HP.
+pub fn apply_entity_stress(conn: &Connection, entity: Entity, amount: i32, source: EventSource) -> AppResult<StressResult> {
+    // Use entity's stress_max if set, otherwise default to 12
+    let effective_stress_max = if entity.stress_max > 0 {
+        entity.stress_max
+    } else {
+        DEFAULT_STRESS_CAP
+    };
+
+    let mut stress_applied = 0;
+    let mut hp_overflow_damage = 0;
+    let mut new_stress = entity.stress_current;
+    let mut new_hp = entity.hp_current;
+
+    if amount > 0 {
+        // Adding stress
+        for _ in 0..amount {
+            if new_stress < effective_stress_max {
+                // Room for stress
+                new_stress += 1;
+                stress_applied += 1;
+            } else {
+                // Stress is maxed - overflow to HP damage
+                new_hp = (new_hp - 1).max(0);
+                hp_overflow_damage += 1;
+            }
+        }
+    } else if amount < 0 {
+        // Removing stress (healing)
+        let reduction = (-amount).min(new_stress);
+        new_stress -= reduction;
+        stress_applied = -reduction;
+    }
+
+    // Update database
+    conn.execute(
+        "UPDATE entities SET stress_current = ?1, hp_current = ?2 WHERE id = ?3",
+        params![new_stress, new_hp, entity.id],
+    )?;
+
+    let updated_entity = Entity {
+        stress_current: new_stress,
+        hp_current: new_hp,
+        ..entity.clone()
+    };
+    record_entity_event(conn, &entity, &updated_entity, &EventDetails::new(EntityEventKind::Stress, source, amount))?;
+
+    Ok(StressResult {
+        entity: updated_entity,
+        stress_applied,
+        hp_overflow_damage,
+    })
+}
+
 #[tauri::command]
 pub fn adjust_entity_stress(
     db: State<Database>,
@@ -348,58 +1162,10 @@ pub fn adjust_entity_stress(
 ) -> AppResult<StressResult> {
     db.with_conn(|conn| {
         let entity = get_entity_by_id(conn, &id)?;
+        let result = apply_entity_stress(conn, entity, amount, EventSource::Manual)?;
 
-        // Use entity's stress_max if set, otherwise default to 12
-        let effective_stress_max = if entity.stress_max > 0 {
-            entity.stress_max
-        } else {
-            DEFAULT_STRESS_CAP
-        };
-
-        let mut stress_applied = 0;
-        let mut hp_overflow_damage = 0;
-        let mut new_stress = entity.stress_current;
-        let mut new_hp = entity.hp_current;
-
-        if amount > 0 {
-            // Adding stress
-            for _ in 0..amount {
-                if new_stress < effective_stress_max {
-                    // Room for stress
-                    new_stress += 1;
-                    stress_applied += 1;
-                } else {
-                    // Stress is maxed - overflow to HP damage
-                    new_hp = (new_hp - 1).max(0);
-                    hp_overflow_damage += 1;
-                }
-            }
-        } else if amount < 0 {
-            // Removing stress (healing)
-            let reduction = (-amount).min(new_stress);
-            new_stress -= reduction;
-            stress_applied = -reduction;
-        }
-
-        // Update database
-        conn.execute(
-            "UPDATE entities SET stress_current = ?1, hp_current = ?2 WHERE id = ?3",
-            params![new_stress, new_hp, id],
-        )?;
-
-        let updated_entity = Entity {
-            stress_current: new_stress,
-            hp_current: new_hp,
-            ..entity
-        };
-
-        emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
-
-        Ok(StressResult {
-            entity: updated_entity,
-            stress_applied,
-            hp_overflow_damage,
-        })
+        emit_entities_update(&app, conn, &result.entity.campaign_id)?;
+        Ok(result)
     })
 }
 
@@ -454,6 +1220,143 @@ pub fn update_entity_name(
     })
 }
 
+/// Replace an entity's whole stat block
+#[tauri::command]
+pub fn update_entity_stat_block(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    stat_block: AdversaryStatBlock,
+) -> AppResult<Entity> {
+    validate_stat_block(&stat_block)?;
+
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &id)?;
+
+        let mut values = stat_block_params(&stat_block)?;
+        let assignments = STAT_BLOCK_COLUMNS
+            .split(", ")
+            .enumerate()
+            .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.push(Box::new(id.clone()));
+        conn.execute(
+            &format!("UPDATE entities SET {} WHERE id = ?{}", assignments, values.len()),
+            rusqlite::params_from_iter(values.iter()),
+        )?;
+
+        let updated_entity = Entity { stat_block, ..entity };
+
+        emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
+        Ok(updated_entity)
+    })
+}
+
+/// Fields to change on an entity; anything left out stays as it is
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateEntity {
+    pub name: Option<String>,
+    pub hp_current: Option<i32>,
+    pub hp_max: Option<i32>,
+    pub stress_current: Option<i32>,
+    pub stress_max: Option<i32>,
+    pub thresholds: Option<DamageThresholds>,
+    pub visible_to_players: Option<bool>,
+    pub entity_type: Option<EntityType>,
+    pub armor_marked: Option<i32>,
+    pub stat_block: Option<AdversaryStatBlock>,
+}
+
+fn validate_thresholds(thresholds: &DamageThresholds) -> AppResult<()> {
+    if thresholds.minor < 1 || thresholds.minor >= thresholds.major || thresholds.major >= thresholds.severe {
+        return Err(AppError::Validation(format!(
+            "Thresholds must be ascending and positive (got {}/{}/{})",
+            thresholds.minor, thresholds.major, thresholds.severe
+        )));
+    }
+    Ok(())
+}
+
+/// Change any of an entity's fields at once. Current HP, Stress and marked
+/// Armor are re-clamped when their maximums shrink, and Stress is capped at 12.
+#[tauri::command]
+pub fn update_entity(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+    data: UpdateEntity,
+) -> AppResult<Entity> {
+    if data.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::Validation("Entity name cannot be empty".to_string()));
+    }
+    if data.hp_max.is_some_and(|hp| hp < 1) {
+        return Err(AppError::Validation("Max HP must be at least 1".to_string()));
+    }
+    if data.stress_max.is_some_and(|stress| stress < 0) {
+        return Err(AppError::Validation("Max Stress cannot be negative".to_string()));
+    }
+    if let Some(thresholds) = &data.thresholds {
+        validate_thresholds(thresholds)?;
+    }
+    if let Some(stat_block) = &data.stat_block {
+        validate_stat_block(stat_block)?;
+    }
+
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &id)?;
+
+        let hp_max = data.hp_max.unwrap_or(entity.hp_max);
+        let stress_max = data.stress_max.unwrap_or(entity.stress_max).min(DEFAULT_STRESS_CAP);
+        // A stress_max of 0 means the default cap, as in apply_entity_stress
+        let effective_stress_max = if stress_max > 0 { stress_max } else { DEFAULT_STRESS_CAP };
+        let stat_block = data.stat_block.unwrap_or(entity.stat_block);
+        let updated_entity = Entity {
+            name: data.name.map(|n| n.trim().to_string()).unwrap_or(entity.name),
+            hp_current: data.hp_current.unwrap_or(entity.hp_current).clamp(0, hp_max),
+            hp_max,
+            stress_current: data.stress_current.unwrap_or(entity.stress_current).clamp(0, effective_stress_max),
+            stress_max,
+            thresholds: data.thresholds.unwrap_or(entity.thresholds),
+            visible_to_players: data.visible_to_players.unwrap_or(entity.visible_to_players),
+            entity_type: data.entity_type.unwrap_or(entity.entity_type),
+            armor_marked: data.armor_marked.unwrap_or(entity.armor_marked).clamp(0, stat_block.armor_slots.max(0)),
+            stat_block,
+            ..entity
+        };
+
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(updated_entity.name.clone()),
+            Box::new(updated_entity.hp_current),
+            Box::new(updated_entity.hp_max),
+            Box::new(updated_entity.stress_current),
+            Box::new(updated_entity.stress_max),
+            Box::new(updated_entity.thresholds.minor),
+            Box::new(updated_entity.thresholds.major),
+            Box::new(updated_entity.thresholds.severe),
+            Box::new(updated_entity.visible_to_players),
+            Box::new(updated_entity.entity_type.as_str()),
+            Box::new(updated_entity.armor_marked),
+        ];
+        values.extend(stat_block_params(&updated_entity.stat_block)?);
+        let assignments = "name, hp_current, hp_max, stress_current, stress_max, threshold_minor, threshold_major, threshold_severe, visible_to_players, entity_type, armor_marked"
+            .split(", ")
+            .chain(STAT_BLOCK_COLUMNS.split(", "))
+            .enumerate()
+            .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.push(Box::new(id.clone()));
+        conn.execute(
+            &format!("UPDATE entities SET {} WHERE id = ?{}", assignments, values.len()),
+            rusqlite::params_from_iter(values.iter()),
+        )?;
+
+        emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
+        Ok(updated_entity)
+    })
+}
+
 #[tauri::command]
 pub fn toggle_entity_visibility(
     db: State<Database>,
@@ -497,8 +1400,407 @@ pub fn set_all_entities_visibility(
             params![visible as i32, campaign_id],
         )?;
 
-        let entities = get_entities_for_campaign(conn, &campaign_id)?;
+        let entities = get_entities_for_campaign(conn, &campaign_id, false)?;
         emit_entities_update(&app, conn, &campaign_id)?;
         Ok(entities)
     })
 }
+
+/// Dedupe a selection and check every entity is in the current campaign,
+/// so one `entities-updated` covers the whole operation
+fn validate_selection(conn: &Connection, ids: Vec<String>) -> AppResult<(String, Vec<String>)> {
+    if ids.is_empty() {
+        return Err(AppError::Validation("No entities selected".to_string()));
+    }
+    let campaign_id = get_required_campaign_id(conn)?;
+
+    let mut selected: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if selected.contains(&id) {
+            continue;
+        }
+        let entity = get_entity_by_id(conn, &id)?;
+        if entity.campaign_id != campaign_id {
+            return Err(AppError::Validation(format!("{} belongs to a different campaign", entity.name)));
+        }
+        selected.push(id);
+    }
+
+    Ok((campaign_id, selected))
+}
+
+/// Deal the same damage to every selected entity, each against its own
+/// thresholds, e.g. an area attack. Targets are resolved in order, so a
+/// Minion already taken out by spillover takes no further damage.
+#[tauri::command]
+pub fn apply_damage_to_entities(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+    damage: i32,
+    damage_type: Option<DamageType>,
+    direct: Option<bool>,
+) -> AppResult<Vec<DamageResult>> {
+    let input = DamageInput {
+        amount: damage,
+        damage_type: damage_type.unwrap_or_default(),
+        direct: direct.unwrap_or(false),
+    };
+
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let entity = get_entity_by_id(&tx, id)?;
+            results.push(apply_damage_to_entity(&tx, entity, input, EventSource::Bulk)?);
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(results)
+    })
+}
+
+#[tauri::command]
+pub fn update_entities_hp(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+    amount: i32,
+) -> AppResult<Vec<Entity>> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let entity = get_entity_by_id(&tx, id)?;
+            updated.push(apply_entity_hp_change(&tx, entity, amount, EventSource::Bulk)?);
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(updated)
+    })
+}
+
+#[tauri::command]
+pub fn adjust_entities_stress(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+    amount: i32,
+) -> AppResult<Vec<StressResult>> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let entity = get_entity_by_id(&tx, id)?;
+            results.push(apply_entity_stress(&tx, entity, amount, EventSource::Bulk)?);
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(results)
+    })
+}
+
+/// Copy each selected entity as it stands, minus its conditions, under the
+/// next free number, e.g. "Bandit 6". Copies stay in their original's group.
+#[tauri::command]
+pub fn duplicate_entities(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+) -> AppResult<Vec<Entity>> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut copies = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let entity = get_entity_by_id(&tx, id)?;
+            let copy = Entity {
+                id: Uuid::new_v4().to_string(),
+                name: next_instance_name(&tx, &campaign_id, &entity.name)?,
+                conditions: Vec::new(),
+                ..entity
+            };
+            insert_entity(&tx, &copy)?;
+            copies.push(copy);
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(copies)
+    })
+}
+
+#[tauri::command]
+pub fn delete_entities(db: State<Database>, app: tauri::AppHandle, ids: Vec<String>) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        for id in &ids {
+            tx.execute("DELETE FROM entities WHERE id = ?1", [id])?;
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(())
+    })
+}
+
+/// Show or hide a selection of entities from players
+#[tauri::command]
+pub fn set_entities_visibility(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+    visible: bool,
+) -> AppResult<Vec<Entity>> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = Vec::with_capacity(ids.len());
+        for id in &ids {
+            tx.execute(
+                "UPDATE entities SET visible_to_players = ?1 WHERE id = ?2",
+                params![visible as i32, id],
+            )?;
+            updated.push(get_entity_by_id(&tx, id)?);
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(updated)
+    })
+}
+
+// ============================================================================
+// Minion Groups
+// ============================================================================
+
+/// Put entities into a new group so Minion spillover damage can reach them
+#[tauri::command]
+pub fn group_entities(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+) -> AppResult<Vec<Entity>> {
+    db.with_conn(|conn| {
+        let (campaign_id, ids) = validate_selection(conn, ids)?;
+        if ids.len() < 2 {
+            return Err(AppError::Validation("A group needs at least two entities".to_string()));
+        }
+        let group_id = Uuid::new_v4().to_string();
+
+        let tx = conn.unchecked_transaction()?;
+        let mut grouped = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let entity = get_entity_by_id(&tx, id)?;
+            tx.execute(
+                "UPDATE entities SET group_id = ?1 WHERE id = ?2",
+                params![group_id, id],
+            )?;
+            grouped.push(Entity { group_id: Some(group_id.clone()), ..entity });
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &campaign_id)?;
+        Ok(grouped)
+    })
+}
+
+#[tauri::command]
+pub fn ungroup_entity(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    id: String,
+) -> AppResult<Entity> {
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &id)?;
+
+        conn.execute("UPDATE entities SET group_id = NULL WHERE id = ?1", [&id])?;
+
+        let updated_entity = Entity { group_id: None, ..entity };
+
+        emit_entities_update(&app, conn, &updated_entity.campaign_id)?;
+        Ok(updated_entity)
+    })
+}
+
+// ============================================================================
+// Event History
+// ============================================================================
+
+const EVENT_COLUMNS: &str = "id, entity_id, campaign_id, kind, source, raw_amount, damage_type, threshold_hit, hp_before, hp_after, stress_before, stress_after, armor_before, armor_after, undone, created_at, parent_event_id";
+
+/// Most events returned by one timeline request
+const MAX_EVENTS: u32 = 500;
+
+fn row_to_event(row: &Row) -> rusqlite::Result<EntityEvent> {
+    Ok(EntityEvent {
+        id: row.get(0)?,
+        entity_id: row.get(1)?,
+        campaign_id: row.get(2)?,
+        kind: EntityEventKind::from_str(&row.get::<_, String>(3)?),
+        source: EventSource::from_str(&row.get::<_, String>(4)?),
+        raw_amount: row.get(5)?,
+        damage_type: row.get::<_, Option<String>>(6)?.map(|t| DamageType::from_str(&t)),
+        threshold_hit: row.get(7)?,
+        hp_before: row.get(8)?,
+        hp_after: row.get(9)?,
+        stress_before: row.get(10)?,
+        stress_after: row.get(11)?,
+        armor_before: row.get(12)?,
+        armor_after: row.get(13)?,
+        undone: row.get(14)?,
+        created_at: row.get(15)?,
+        parent_event_id: row.get(16)?,
+    })
+}
+
+/// Record a change to an entity's HP, Stress or Armor for its timeline
+pub fn record_entity_event(conn: &Connection, before: &Entity, after: &Entity, details: &EventDetails) -> AppResult<()> {
+    insert_entity_event(conn, &Uuid::new_v4().to_string(), before, after, details)
+}
+
+fn insert_entity_event(
+    conn: &Connection,
+    id: &str,
+    before: &Entity,
+    after: &Entity,
+    details: &EventDetails,
+) -> AppResult<()> {
+    conn.execute(
+        &format!(
+            "INSERT INTO entity_events ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, 0, datetime('now'), ?15)",
+            EVENT_COLUMNS
+        ),
+        params![
+            id,
+            before.id,
+            before.campaign_id,
+            details.kind.as_str(),
+            details.source.as_str(),
+            details.raw_amount,
+            details.damage_type.map(|t| t.as_str()),
+            details.threshold_hit,
+            before.hp_current,
+            after.hp_current,
+            before.stress_current,
+            after.stress_current,
+            before.armor_marked,
+            after.armor_marked,
+            details.parent_event_id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// An entity's HP and Stress changes, newest first
+#[tauri::command]
+pub fn get_entity_events(db: State<Database>, entity_id: String, limit: Option<u32>) -> AppResult<Vec<EntityEvent>> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM entity_events WHERE entity_id = ?1 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+            EVENT_COLUMNS
+        ))?;
+
+        let events = stmt
+            .query_map(params![entity_id, limit.unwrap_or(MAX_EVENTS).min(MAX_EVENTS)], row_to_event)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    })
+}
+
+/// Restore the HP, Stress and Armor an entity had before its most recent
+/// change that hasn't been undone, returning the undone event. Calling it
+/// again steps further back. Undoing a Minion hit also revives the Minions
+/// its spillover defeated. Refuses when any of them no longer matches that
+/// change's result.
+#[tauri::command]
+pub fn undo_last_entity_event(db: State<Database>, app: tauri::AppHandle, entity_id: String) -> AppResult<EntityEvent> {
+    db.with_conn(|conn| {
+        let entity = get_entity_by_id(conn, &entity_id)?;
+        let event = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM entity_events WHERE entity_id = ?1 AND undone = 0 ORDER BY created_at DESC, rowid DESC LIMIT 1",
+                    EVENT_COLUMNS
+                ),
+                [&entity_id],
+                row_to_event,
+            )
+            .map_err(|_| AppError::InvalidOperation(format!("{} has no changes to undo", entity.name)))?;
+
+        if let Some(parent_id) = &event.parent_event_id {
+            let parent_pending: bool = conn.query_row(
+                "SELECT COUNT(*) > 0 FROM entity_events WHERE id = ?1 AND undone = 0",
+                [parent_id],
+                |row| row.get(0),
+            )?;
+            if parent_pending {
+                return Err(AppError::InvalidOperation(format!(
+                    "{} was defeated by Minion spillover; undo the hit that caused it",
+                    entity.name
+                )));
+            }
+        }
+
+        // A Minion hit is undone together with the spillover it caused
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM entity_events WHERE parent_event_id = ?1 AND undone = 0",
+            EVENT_COLUMNS
+        ))?;
+        let spillover = stmt
+            .query_map([&event.id], row_to_event)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut changes = vec![(entity.clone(), event.clone())];
+        for child in spillover {
+            changes.push((get_entity_by_id(conn, &child.entity_id)?, child));
+        }
+
+        // Edits and armor repairs aren't recorded, so restoring over one
+        // would silently discard it
+        for (changed, change) in &changes {
+            if (changed.hp_current, changed.stress_current, changed.armor_marked)
+                != (change.hp_after, change.stress_after, change.armor_after)
+            {
+                return Err(AppError::InvalidOperation(format!(
+                    "{} has changed since its last recorded change and can't be undone",
+                    changed.name
+                )));
+            }
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        for (changed, change) in &changes {
+            // A stress_max of 0 means the default cap, as in apply_entity_stress
+            let effective_stress_max = if changed.stress_max > 0 { changed.stress_max } else { DEFAULT_STRESS_CAP };
+            tx.execute(
+                "UPDATE entities SET hp_current = ?1, stress_current = ?2, armor_marked = ?3 WHERE id = ?4",
+                params![
+                    change.hp_before.clamp(0, changed.hp_max),
+                    change.stress_before.clamp(0, effective_stress_max),
+                    change.armor_before.clamp(0, changed.stat_block.armor_slots.max(0)),
+                    changed.id
+                ],
+            )?;
+            tx.execute("UPDATE entity_events SET undone = 1 WHERE id = ?1", [&change.id])?;
+        }
+        tx.commit()?;
+
+        emit_entities_update(&app, conn, &entity.campaign_id)?;
+        Ok(EntityEvent { undone: true, ..event })
+    })
+}
@@ -27,11 +27,24 @@ fn get_required_campaign_id(conn: &rusqlite::Connection) -> AppResult<String> {
         .ok_or_else(|| AppError::InvalidOperation("No campaign selected".to_string()))
 }
 
-fn emit_fear_update(app: &tauri::AppHandle, level: i32, campaign_id: &str) -> AppResult<()> {
+pub fn emit_fear_update(app: &tauri::AppHandle, level: i32, campaign_id: &str) -> AppResult<()> {
     app.emit(EVENT_NAME, FearLevelPayload { level, campaign_id: campaign_id.to_string() })
         .map_err(|e| AppError::EmitError(e.to_string()))
 }
 
+/// Adjust a campaign's fear level by a delta without emitting, returning the new level
+pub fn apply_fear_adjustment(conn: &rusqlite::Connection, campaign_id: &str, amount: i32) -> AppResult<i32> {
+    let campaign = get_campaign_by_id(conn, campaign_id)?;
+    let new_level = (campaign.fear_level + amount).max(0);
+
+    conn.execute(
+        "UPDATE campaigns SET fear_level = ?1 WHERE id = ?2",
+        params![new_level, campaign_id],
+    )?;
+
+    Ok(new_level)
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
@@ -55,13 +68,7 @@ pub fn adjust_fear_level(
 ) -> AppResult<i32> {
     db.with_conn(|conn| {
         let campaign_id = get_required_campaign_id(conn)?;
-        let campaign = get_campaign_by_id(conn, &campaign_id)?;
-        let new_level = (campaign.fear_level + amount).max(0);
-
-        conn.execute(
-            "UPDATE campaigns SET fear_level = ?1 WHERE id = ?2",
-            params![new_level, campaign_id],
-        )?;
+        let new_level = apply_fear_adjustment(conn, &campaign_id, amount)?;
 
         emit_fear_update(&app, new_level, &campaign_id)?;
         Ok(new_level)
@@ -1,8 +1,18 @@
+pub mod adversary_library;
+pub mod attack;
 pub mod campaign;
+pub mod condition;
 pub mod countdown;
 pub mod database;
 pub mod dice;
+pub mod dice_engine;
+pub mod dice_fairness;
+pub mod dice_probability;
+pub mod dice_statistics;
+pub mod encounter;
 pub mod entity;
 pub mod error;
 pub mod fear_tracker;
-pub mod player_character;
\ No newline at end of file
+pub mod player_character;
+pub mod roll_macro;
+pub mod spotlight;
\ No newline at end of file
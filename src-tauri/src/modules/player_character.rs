@@ -1,10 +1,11 @@
-use rusqlite::{params, Row};
+use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use super::condition::{get_conditions_by_target, get_conditions_for_target, Condition, ConditionTarget};
 use super::database::Database;
-use super::error::AppResult;
+use super::error::{AppError, AppResult};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PlayerCharacter {
@@ -52,6 +53,12 @@ pub struct PlayerCharacter {
 
     pub created_at: String,
     pub updated_at: String,
+
+    /// Counted in the party for encounter budgets
+    pub is_active: bool,
+
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -126,6 +133,7 @@ pub struct UpdatePlayerCharacter {
     pub stress_current: Option<i32>,
     pub stress_max: Option<i32>,
     pub experiences: Option<String>,
+    pub is_active: Option<bool>,
     pub background: Option<String>,
     pub notes: Option<String>,
 }
@@ -163,6 +171,8 @@ fn row_to_player_character(row: &Row) -> rusqlite::Result<PlayerCharacter> {
         notes: row.get(28)?,
         created_at: row.get(29)?,
         updated_at: row.get(30)?,
+        is_active: row.get(31)?,
+        conditions: Vec::new(),
     })
 }
 
@@ -170,7 +180,35 @@ const SELECT_COLUMNS: &str = "id, campaign_id, name, ancestry, community, class,
     attr_agility, attr_strength, attr_finesse, attr_instinct, attr_presence, attr_knowledge,
     hp_current, hp_max, threshold_minor, threshold_major, threshold_severe,
     armor_current, armor_max, evasion, hope, stress_current, stress_max,
-    experiences, background, notes, created_at, updated_at";
+    experiences, background, notes, created_at, updated_at, is_active";
+
+pub fn get_player_character_by_id(conn: &Connection, id: &str) -> AppResult<PlayerCharacter> {
+    let mut character = conn
+        .query_row(
+            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
+            params![id],
+            row_to_player_character,
+        )
+        .map_err(|_| AppError::EntityNotFound(format!("Player character not found: {}", id)))?;
+
+    character.conditions = get_conditions_for_target(conn, ConditionTarget::PlayerCharacter, id)?;
+    Ok(character)
+}
+
+pub fn emit_player_character_update(app: &AppHandle, character: &PlayerCharacter) {
+    let _ = app.emit("player-characters-updated", &character.campaign_id);
+    let _ = app.emit("player-character-updated", character);
+}
+
+/// Adjust a character's Hope by a delta without emitting, returning the updated character
+pub fn apply_hope_adjustment(conn: &Connection, id: &str, amount: i32) -> AppResult<PlayerCharacter> {
+    conn.execute(
+        "UPDATE player_characters SET hope = MAX(0, hope + ?1), updated_at = datetime('now') WHERE id = ?2",
+        params![amount, id],
+    )?;
+
+    get_player_character_by_id(conn, id)
+}
 
 #[tauri::command]
 pub fn create_player_character(
@@ -216,11 +254,7 @@ pub fn create_player_character(
             ],
         )?;
 
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
+        let character = get_player_character_by_id(conn, &id)?;
 
         let _ = app.emit("player-characters-updated", &campaign_id);
 
@@ -239,10 +273,15 @@ pub fn get_player_characters(
             SELECT_COLUMNS
         ))?;
 
-        let characters = stmt
+        let mut characters = stmt
             .query_map(params![campaign_id], row_to_player_character)?
             .collect::<Result<Vec<_>, _>>()?;
 
+        let mut conditions = get_conditions_by_target(conn, &campaign_id, ConditionTarget::PlayerCharacter)?;
+        for character in &mut characters {
+            character.conditions = conditions.remove(&character.id).unwrap_or_default();
+        }
+
         Ok(characters)
     })
 }
@@ -253,11 +292,7 @@ pub fn get_player_character(
     id: String,
 ) -> AppResult<PlayerCharacter> {
     db.with_conn(|conn| {
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
+        let character = get_player_character_by_id(conn, &id)?;
 
         Ok(character)
     })
@@ -309,6 +344,7 @@ pub fn update_player_character(
         add_field!(stress_current, "stress_current");
         add_field!(stress_max, "stress_max");
         add_field!(experiences, "experiences");
+        add_field!(is_active, "is_active");
         add_field!(background, "background");
         add_field!(notes, "notes");
 
@@ -323,11 +359,7 @@ pub fn update_player_character(
         let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
         conn.execute(&query, params.as_slice())?;
 
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
+        let character = get_player_character_by_id(conn, &id)?;
 
         let _ = app.emit("player-characters-updated", &character.campaign_id);
         let _ = app.emit("player-character-updated", &character);
@@ -358,6 +390,16 @@ pub fn delete_player_character(
     })
 }
 
+/// Adjust a character's HP by a delta without emitting, returning the updated character
+pub fn apply_hp_adjustment(conn: &Connection, id: &str, amount: i32) -> AppResult<PlayerCharacter> {
+    conn.execute(
+        "UPDATE player_characters SET hp_current = MAX(0, hp_current + ?1), updated_at = datetime('now') WHERE id = ?2",
+        params![amount, id],
+    )?;
+
+    get_player_character_by_id(conn, id)
+}
+
 #[tauri::command]
 pub fn adjust_player_hp(
     db: State<Database>,
@@ -366,19 +408,9 @@ pub fn adjust_player_hp(
     amount: i32,
 ) -> AppResult<PlayerCharacter> {
     db.with_conn(|conn| {
-        conn.execute(
-            "UPDATE player_characters SET hp_current = MAX(0, hp_current + ?1), updated_at = datetime('now') WHERE id = ?2",
-            params![amount, id],
-        )?;
-
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
+        let character = apply_hp_adjustment(conn, &id, amount)?;
 
-        let _ = app.emit("player-characters-updated", &character.campaign_id);
-        let _ = app.emit("player-character-updated", &character);
+        emit_player_character_update(&app, &character);
 
         Ok(character)
     })
@@ -392,19 +424,9 @@ pub fn adjust_player_hope(
     amount: i32,
 ) -> AppResult<PlayerCharacter> {
     db.with_conn(|conn| {
-        conn.execute(
-            "UPDATE player_characters SET hope = MAX(0, hope + ?1), updated_at = datetime('now') WHERE id = ?2",
-            params![amount, id],
-        )?;
+        let character = apply_hope_adjustment(conn, &id, amount)?;
 
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
-
-        let _ = app.emit("player-characters-updated", &character.campaign_id);
-        let _ = app.emit("player-character-updated", &character);
+        emit_player_character_update(&app, &character);
 
         Ok(character)
     })
@@ -421,6 +443,60 @@ pub struct StressResult {
     pub hp_overflow_damage: i32,
 }
 
+/// Mark (positive) or clear (negative) Stress without emitting. Stress past
+/// the cap overflows into HP.
+pub fn apply_stress_adjustment(conn: &Connection, id: &str, amount: i32) -> AppResult<StressResult> {
+    // Get current character state
+    let character = get_player_character_by_id(conn, id)?;
+
+    // Use character's stress_max if set, otherwise default to 12
+    let effective_stress_max = if character.stress_max > 0 {
+        character.stress_max
+    } else {
+        DEFAULT_STRESS_CAP
+    };
+
+    let mut stress_applied = 0;
+    let mut hp_overflow_damage = 0;
+    let mut new_stress = character.stress_current;
+    let mut new_hp = character.hp_current;
+
+    if amount > 0 {
+        // Adding stress
+        for _ in 0..amount {
+            if new_stress < effective_stress_max {
+                // Room for stress
+                new_stress += 1;
+                stress_applied += 1;
+            } else {
+                // Stress is maxed - overflow to HP damage
+                new_hp = (new_hp - 1).max(0);
+                hp_overflow_damage += 1;
+            }
+        }
+    } else if amount < 0 {
+        // Removing stress (healing)
+        let reduction = (-amount).min(new_stress);
+        new_stress -= reduction;
+        stress_applied = -reduction;
+    }
+
+    // Update database
+    conn.execute(
+        "UPDATE player_characters SET stress_current = ?1, hp_current = ?2, updated_at = datetime('now') WHERE id = ?3",
+        params![new_stress, new_hp, id],
+    )?;
+
+    // Fetch updated character
+    let updated_character = get_player_character_by_id(conn, id)?;
+
+    Ok(StressResult {
+        character: updated_character,
+        stress_applied,
+        hp_overflow_damage,
+    })
+}
+
 #[tauri::command]
 pub fn adjust_player_stress(
     db: State<Database>,
@@ -429,66 +505,11 @@ pub fn adjust_player_stress(
     amount: i32,
 ) -> AppResult<StressResult> {
     db.with_conn(|conn| {
-        // Get current character state
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
-
-        // Use character's stress_max if set, otherwise default to 12
-        let effective_stress_max = if character.stress_max > 0 {
-            character.stress_max
-        } else {
-            DEFAULT_STRESS_CAP
-        };
-
-        let mut stress_applied = 0;
-        let mut hp_overflow_damage = 0;
-        let mut new_stress = character.stress_current;
-        let mut new_hp = character.hp_current;
-
-        if amount > 0 {
-            // Adding stress
-            for _ in 0..amount {
-                if new_stress < effective_stress_max {
-                    // Room for stress
-                    new_stress += 1;
-                    stress_applied += 1;
-                } else {
-                    // Stress is maxed - overflow to HP damage
-                    new_hp = (new_hp - 1).max(0);
-                    hp_overflow_damage += 1;
-                }
-            }
-        } else if amount < 0 {
-            // Removing stress (healing)
-            let reduction = (-amount).min(new_stress);
-            new_stress -= reduction;
-            stress_applied = -reduction;
-        }
+        let result = apply_stress_adjustment(conn, &id, amount)?;
 
-        // Update database
-        conn.execute(
-            "UPDATE player_characters SET stress_current = ?1, hp_current = ?2, updated_at = datetime('now') WHERE id = ?3",
-            params![new_stress, new_hp, id],
-        )?;
+        emit_player_character_update(&app, &result.character);
 
-        // Fetch updated character
-        let updated_character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
-
-        let _ = app.emit("player-characters-updated", &updated_character.campaign_id);
-        let _ = app.emit("player-character-updated", &updated_character);
-
-        Ok(StressResult {
-            character: updated_character,
-            stress_applied,
-            hp_overflow_damage,
-        })
+        Ok(result)
     })
 }
 
@@ -505,11 +526,7 @@ pub fn adjust_player_armor(
             params![amount, id],
         )?;
 
-        let character = conn.query_row(
-            &format!("SELECT {} FROM player_characters WHERE id = ?1", SELECT_COLUMNS),
-            params![id],
-            row_to_player_character,
-        )?;
+        let character = get_player_character_by_id(conn, &id)?;
 
         let _ = app.emit("player-characters-updated", &character.campaign_id);
         let _ = app.emit("player-character-updated", &character);
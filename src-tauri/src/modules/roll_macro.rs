@@ -0,0 +1,399 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use super::database::Database;
+use super::dice::{perform_duality_roll, perform_roll, DiceRoll, RollSettings, RollType};
+use super::dice_engine::{self, RollOptions, RollVariables};
+use super::error::{AppError, AppResult};
+use super::player_character::{get_player_character_by_id, PlayerCharacter};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Bonus an Experience gives when its entry doesn't specify one
+const DEFAULT_EXPERIENCE_MODIFIER: i32 = 2;
+
+/// Variables every character provides, besides `exp:<Experience>`
+const CHARACTER_VARIABLES: [&str; 9] = [
+    "agility",
+    "strength",
+    "finesse",
+    "instinct",
+    "presence",
+    "knowledge",
+    "proficiency",
+    "level",
+    "evasion",
+];
+
+/// A saved roll preset. For duality macros `notation` is the modifier
+/// expression (e.g. `@agility+@exp:Stealth`) added to the Hope and Fear dice.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RollMacro {
+    pub id: String,
+    pub campaign_id: String,
+    /// `None` for campaign-wide macros
+    pub player_character_id: Option<String>,
+    pub name: String,
+    pub notation: String,
+    pub roll_type: RollType,
+    pub difficulty: Option<i32>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRollMacro {
+    pub name: String,
+    pub notation: String,
+    #[serde(default)]
+    pub roll_type: RollType,
+    pub difficulty: Option<i32>,
+    pub player_character_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRollMacro {
+    pub name: Option<String>,
+    pub notation: Option<String>,
+    pub roll_type: Option<RollType>,
+    /// Omitted keeps the current Difficulty, `null` removes it
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub difficulty: Option<Option<i32>>,
+}
+
+/// Tell an explicit `null` (`Some(None)`) apart from a missing field (`None`)
+fn present_or_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Result of rolling a macro, whichever kind of roll it was
+#[derive(Clone, Serialize)]
+pub struct MacroRollResult {
+    /// Notation after variables were replaced with the character's values
+    pub resolved_notation: String,
+    pub roll: DiceRoll,
+    /// Duality macros only
+    pub hope: Option<i32>,
+    pub fear: Option<i32>,
+    pub fear_level: Option<i32>,
+    pub character: Option<PlayerCharacter>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn row_to_roll_macro(row: &Row) -> rusqlite::Result<RollMacro> {
+    Ok(RollMacro {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        player_character_id: row.get(2)?,
+        name: row.get(3)?,
+        notation: row.get(4)?,
+        roll_type: RollType::from_str(&row.get::<_, String>(5)?),
+        difficulty: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+const SELECT_COLUMNS: &str = "id, campaign_id, player_character_id, name, notation, roll_type, difficulty, created_at, updated_at";
+
+fn get_roll_macro_by_id(conn: &Connection, id: &str) -> AppResult<RollMacro> {
+    conn.query_row(
+        &format!("SELECT {} FROM roll_macros WHERE id = ?1", SELECT_COLUMNS),
+        params![id],
+        row_to_roll_macro,
+    )
+    .map_err(|_| AppError::EntityNotFound(format!("Roll macro not found: {}", id)))
+}
+
+/// Daggerheart Proficiency: 1 at level 1, +1 at levels 2, 5 and 8
+pub fn proficiency_for_level(level: i32) -> i32 {
+    1 + [2, 5, 8].iter().filter(|&&threshold| level >= threshold).count() as i32
+}
+
+/// Parse the `experiences` JSON of a character. Entries may be objects
+/// (`{"name": "Stealth", "modifier": 2}`) or strings (`"Stealth +2"`,
+/// `"Stealth"`); a missing bonus defaults to +2.
+pub fn parse_experiences(json: &str) -> Vec<(String, i32)> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(json).unwrap_or_default();
+
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            serde_json::Value::String(text) => {
+                let text = text.trim();
+                if let Some((name, bonus)) = text.rsplit_once(char::is_whitespace) {
+                    if let Ok(bonus) = bonus.parse::<i32>() {
+                        return Some((name.trim().to_string(), bonus));
+                    }
+                }
+                Some((text.to_string(), DEFAULT_EXPERIENCE_MODIFIER))
+            }
+            serde_json::Value::Object(fields) => {
+                let name = fields.get("name").or_else(|| fields.get("title"))?.as_str()?;
+                let modifier = ["modifier", "bonus", "value"]
+                    .iter()
+                    .find_map(|key| fields.get(*key)?.as_i64())
+                    .unwrap_or(DEFAULT_EXPERIENCE_MODIFIER as i64);
+                Some((name.trim().to_string(), modifier as i32))
+            }
+            _ => None,
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// Variables for a character: traits, Proficiency, level, Evasion and Experiences
+pub fn character_variables(character: &PlayerCharacter) -> RollVariables {
+    let mut variables = RollVariables::default();
+    variables.set("agility", character.attr_agility);
+    variables.set("strength", character.attr_strength);
+    variables.set("finesse", character.attr_finesse);
+    variables.set("instinct", character.attr_instinct);
+    variables.set("presence", character.attr_presence);
+    variables.set("knowledge", character.attr_knowledge);
+    variables.set("proficiency", proficiency_for_level(character.level));
+    variables.set("level", character.level);
+    variables.set("evasion", character.evasion);
+
+    for (name, modifier) in parse_experiences(&character.experiences) {
+        variables.set_experience(&name, modifier);
+    }
+
+    variables
+}
+
+/// Check a macro's notation parses, with placeholder values for its variables
+fn validate_macro_notation(notation: &str, roll_type: RollType) -> AppResult<()> {
+    let mut placeholders = RollVariables::default();
+    for name in dice_engine::notation_variables(notation)? {
+        let lower = name.to_ascii_lowercase();
+        if !lower.starts_with("exp:") && !CHARACTER_VARIABLES.contains(&lower.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Unknown roll variable: @{} (expected one of {} or @exp:<Experience>)",
+                name,
+                CHARACTER_VARIABLES.map(|v| format!("@{}", v)).join(", ")
+            )));
+        }
+        placeholders.set(&name, 1);
+    }
+
+    let resolved = dice_engine::substitute_variables(notation, &placeholders)?;
+    match roll_type {
+        RollType::Standard => dice_engine::parse_notation(&resolved).map(|_| ()),
+        RollType::Duality if resolved.trim().is_empty() => Ok(()),
+        RollType::Duality => dice_engine::evaluate_constant(&resolved).map(|_| ()),
+    }
+}
+
+fn validate_name(name: &str) -> AppResult<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Macro name cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn emit_roll_macros_update(app: &AppHandle, campaign_id: &str) {
+    let _ = app.emit("roll-macros-updated", campaign_id);
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn create_roll_macro(
+    db: State<Database>,
+    app: AppHandle,
+    campaign_id: String,
+    data: CreateRollMacro,
+) -> AppResult<RollMacro> {
+    validate_name(&data.name)?;
+    validate_macro_notation(&data.notation, data.roll_type)?;
+
+    db.with_conn(|conn| {
+        if let Some(pc_id) = &data.player_character_id {
+            let character = get_player_character_by_id(conn, pc_id)?;
+            if character.campaign_id != campaign_id {
+                return Err(AppError::Validation(
+                    "Player character belongs to a different campaign".to_string(),
+                ));
+            }
+        }
+
+        let id = Uuid::new_v4().to_string();
+
+        conn.execute(
+            "INSERT INTO roll_macros (id, campaign_id, player_character_id, name, notation, roll_type, difficulty)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                campaign_id,
+                data.player_character_id,
+                data.name.trim(),
+                data.notation.trim(),
+                data.roll_type.as_str(),
+                data.difficulty
+            ],
+        )?;
+
+        let roll_macro = get_roll_macro_by_id(conn, &id)?;
+
+        emit_roll_macros_update(&app, &campaign_id);
+
+        Ok(roll_macro)
+    })
+}
+
+/// Macros for a campaign, by name. With `player_character_id`, only the
+/// campaign-wide macros and that character's own.
+#[tauri::command]
+pub fn get_roll_macros(
+    db: State<Database>,
+    campaign_id: String,
+    player_character_id: Option<String>,
+) -> AppResult<Vec<RollMacro>> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM roll_macros
+             WHERE campaign_id = ?1
+               AND (?2 IS NULL OR player_character_id IS NULL OR player_character_id = ?2)
+             ORDER BY name",
+            SELECT_COLUMNS
+        ))?;
+
+        let macros = stmt
+            .query_map(params![campaign_id, player_character_id], row_to_roll_macro)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(macros)
+    })
+}
+
+#[tauri::command]
+pub fn update_roll_macro(
+    db: State<Database>,
+    app: AppHandle,
+    id: String,
+    data: UpdateRollMacro,
+) -> AppResult<RollMacro> {
+    db.with_conn(|conn| {
+        let existing = get_roll_macro_by_id(conn, &id)?;
+
+        let name = data.name.unwrap_or(existing.name);
+        let notation = data.notation.unwrap_or(existing.notation);
+        let roll_type = data.roll_type.unwrap_or(existing.roll_type);
+        let difficulty = data.difficulty.unwrap_or(existing.difficulty);
+
+        validate_name(&name)?;
+        validate_macro_notation(&notation, roll_type)?;
+
+        conn.execute(
+            "UPDATE roll_macros
+             SET name = ?1, notation = ?2, roll_type = ?3, difficulty = ?4, updated_at = datetime('now')
+             WHERE id = ?5",
+            params![name.trim(), notation.trim(), roll_type.as_str(), difficulty, id],
+        )?;
+
+        let roll_macro = get_roll_macro_by_id(conn, &id)?;
+
+        emit_roll_macros_update(&app, &roll_macro.campaign_id);
+
+        Ok(roll_macro)
+    })
+}
+
+#[tauri::command]
+pub fn delete_roll_macro(db: State<Database>, app: AppHandle, id: String) -> AppResult<()> {
+    db.with_conn(|conn| {
+        let existing = get_roll_macro_by_id(conn, &id)?;
+
+        conn.execute("DELETE FROM roll_macros WHERE id = ?1", params![id])?;
+
+        emit_roll_macros_update(&app, &existing.campaign_id);
+
+        Ok(())
+    })
+}
+
+/// Roll a saved macro, resolving its variables against the character it is
+/// bound to (or `player_character_id` for campaign-wide macros) at roll time.
+#[tauri::command]
+pub fn roll_macro(
+    db: State<Database>,
+    app: AppHandle,
+    id: String,
+    player_character_id: Option<String>,
+    options: Option<RollOptions>,
+    shared_with_players: Option<bool>,
+    secret: Option<bool>,
+) -> AppResult<MacroRollResult> {
+    db.with_conn(|conn| {
+        let roll_macro = get_roll_macro_by_id(conn, &id)?;
+
+        let character = match roll_macro.player_character_id.as_ref().or(player_character_id.as_ref()) {
+            Some(pc_id) => Some(get_player_character_by_id(conn, pc_id)?),
+            None => None,
+        };
+
+        let variables = match &character {
+            Some(character) => character_variables(character),
+            None if roll_macro.notation.contains('@') => {
+                return Err(AppError::Validation(format!(
+                    "'{}' uses character variables; choose a player character to roll it for",
+                    roll_macro.name
+                )));
+            }
+            None => RollVariables::default(),
+        };
+
+        let resolved_notation = dice_engine::substitute_variables(&roll_macro.notation, &variables)?;
+        let settings = RollSettings {
+            campaign_id: &roll_macro.campaign_id,
+            player_character_id: character.as_ref().map(|c| c.id.as_str()),
+            options: options.unwrap_or_default(),
+            shared_with_players: shared_with_players.unwrap_or(false),
+            secret: secret.unwrap_or(false),
+        };
+
+        match roll_macro.roll_type {
+            RollType::Standard => {
+                let roll = perform_roll(conn, &app, &resolved_notation, &settings)?;
+                Ok(MacroRollResult {
+                    resolved_notation,
+                    roll,
+                    hope: None,
+                    fear: None,
+                    fear_level: None,
+                    character: None,
+                })
+            }
+            RollType::Duality => {
+                let modifier = if resolved_notation.trim().is_empty() {
+                    0
+                } else {
+                    dice_engine::evaluate_constant(&resolved_notation)?
+                };
+                let result = perform_duality_roll(conn, &app, modifier, roll_macro.difficulty, &settings)?;
+                Ok(MacroRollResult {
+                    resolved_notation,
+                    roll: result.roll,
+                    hope: Some(result.hope),
+                    fear: Some(result.fear),
+                    fear_level: result.fear_level,
+                    character: result.character,
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,387 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, State};
+use uuid::Uuid;
+
+use super::condition::{emit_condition_changes, expire_scene_conditions, tick_conditions};
+use super::database::Database;
+use super::entity::{get_entity_by_id, get_required_campaign_id};
+use super::error::{AppError, AppResult};
+use super::player_character::get_player_character_by_id;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Action tokens each active player character starts a scene with
+const DEFAULT_ACTION_TOKENS: i32 = 3;
+
+/// Most spotlight changes returned at once
+const MAX_HISTORY: u32 = 500;
+
+/// Who holds the spotlight
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpotlightHolder {
+    PlayerCharacter,
+    /// An adversary or NPC, on the GM's side
+    Entity,
+    /// The GM, without a particular entity
+    Gm,
+}
+
+impl SpotlightHolder {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SpotlightHolder::PlayerCharacter => "player_character",
+            SpotlightHolder::Entity => "entity",
+            SpotlightHolder::Gm => "gm",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "player_character" => SpotlightHolder::PlayerCharacter,
+            "entity" => SpotlightHolder::Entity,
+            _ => SpotlightHolder::Gm,
+        }
+    }
+
+    fn is_gm_side(&self) -> bool {
+        *self != SpotlightHolder::PlayerCharacter
+    }
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SpotlightChange {
+    pub id: String,
+    pub campaign_id: String,
+    pub scene: i32,
+    pub holder_type: SpotlightHolder,
+    pub holder_id: Option<String>,
+    pub holder_name: String,
+    pub changed_at: String,
+}
+
+/// How often a player character has had the spotlight this scene
+#[derive(Clone, Serialize, Debug)]
+pub struct CharacterSpotlights {
+    pub player_character_id: String,
+    pub name: String,
+    pub spotlights: i32,
+    /// Tokens left, when action tokens are in use
+    pub action_tokens: Option<i32>,
+}
+
+/// The campaign's spotlight, sent with `spotlight-changed`
+#[derive(Clone, Serialize, Debug)]
+pub struct SpotlightState {
+    pub campaign_id: String,
+    pub scene: i32,
+    pub current: Option<SpotlightChange>,
+    pub use_action_tokens: bool,
+    /// Tokens players have spent this scene, for the GM to act with
+    pub gm_tokens: i32,
+    /// Active player characters, fewest spotlights first
+    pub characters: Vec<CharacterSpotlights>,
+}
+
+// ============================================================================
+// Database Helpers
+// ============================================================================
+
+const SELECT_COLUMNS: &str = "id, campaign_id, scene, holder_type, holder_id, holder_name, changed_at";
+
+fn row_to_change(row: &Row) -> rusqlite::Result<SpotlightChange> {
+    Ok(SpotlightChange {
+        id: row.get(0)?,
+        campaign_id: row.get(1)?,
+        scene: row.get(2)?,
+        holder_type: SpotlightHolder::from_str(&row.get::<_, String>(3)?),
+        holder_id: row.get(4)?,
+        holder_name: row.get(5)?,
+        changed_at: row.get(6)?,
+    })
+}
+
+/// The campaign's scene settings, creating them on first use
+fn get_or_create_settings(conn: &Connection, campaign_id: &str) -> AppResult<(i32, bool, i32)> {
+    conn.execute(
+        "INSERT OR IGNORE INTO spotlight_state (campaign_id) VALUES (?1)",
+        [campaign_id],
+    )?;
+
+    let settings = conn.query_row(
+        "SELECT scene, use_action_tokens, gm_tokens FROM spotlight_state WHERE campaign_id = ?1",
+        [campaign_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    Ok(settings)
+}
+
+fn latest_change(conn: &Connection, campaign_id: &str, scene: i32) -> AppResult<Option<SpotlightChange>> {
+    let change = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM spotlight_changes WHERE campaign_id = ?1 AND scene = ?2 ORDER BY changed_at DESC, rowid DESC LIMIT 1",
+                SELECT_COLUMNS
+            ),
+            params![campaign_id, scene],
+            row_to_change,
+        )
+        .optional()?;
+    Ok(change)
+}
+
+pub fn get_spotlight_state(conn: &Connection, campaign_id: &str) -> AppResult<SpotlightState> {
+    let (scene, use_action_tokens, gm_tokens) = get_or_create_settings(conn, campaign_id)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT pc.id, pc.name,
+                (SELECT COUNT(*) FROM spotlight_changes sc
+                 WHERE sc.campaign_id = pc.campaign_id AND sc.scene = ?2
+                   AND sc.holder_type = 'player_character' AND sc.holder_id = pc.id),
+                (SELECT tokens FROM action_tokens t WHERE t.player_character_id = pc.id)
+         FROM player_characters pc
+         WHERE pc.campaign_id = ?1 AND pc.is_active = 1
+         ORDER BY 3, pc.name",
+    )?;
+    let characters = stmt
+        .query_map(params![campaign_id, scene], |row| {
+            Ok(CharacterSpotlights {
+                player_character_id: row.get(0)?,
+                name: row.get(1)?,
+                spotlights: row.get(2)?,
+                action_tokens: if use_action_tokens {
+                    Some(row.get::<_, Option<i32>>(3)?.unwrap_or(0))
+                } else {
+                    None
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SpotlightState {
+        campaign_id: campaign_id.to_string(),
+        scene,
+        current: latest_change(conn, campaign_id, scene)?,
+        use_action_tokens,
+        gm_tokens,
+        characters,
+    })
+}
+
+/// The spotlight as players may see it: an entity hidden from them is shown
+/// as the GM holding it
+fn player_safe_state(conn: &Connection, state: &SpotlightState) -> SpotlightState {
+    let mut state = state.clone();
+    if let Some(change) = &mut state.current {
+        if change.holder_type == SpotlightHolder::Entity {
+            let visible = match &change.holder_id {
+                Some(id) => get_entity_by_id(conn, id).map(|e| e.visible_to_players).unwrap_or(false),
+                None => false,
+            };
+            if !visible {
+                change.holder_type = SpotlightHolder::Gm;
+                change.holder_id = None;
+                change.holder_name = "GM".to_string();
+            }
+        }
+    }
+    state
+}
+
+/// `spotlight-changed` goes to every window, including the player view, so
+/// it carries the player-safe state
+fn emit_spotlight_changed(app: &tauri::AppHandle, conn: &Connection, state: &SpotlightState) -> AppResult<()> {
+    app.emit("spotlight-changed", player_safe_state(conn, state))
+        .map_err(|e| AppError::EmitError(e.to_string()))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_spotlight(db: State<Database>) -> AppResult<SpotlightState> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        get_spotlight_state(conn, &campaign_id)
+    })
+}
+
+/// Pass the spotlight. With action tokens on, a player character spends one
+/// to take it and the GM gains it. When the spotlight leaves the GM's side
+/// for a player character, the GM spotlight ends and conditions tick.
+#[tauri::command]
+pub fn set_spotlight(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    holder_type: SpotlightHolder,
+    holder_id: Option<String>,
+) -> AppResult<SpotlightState> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        let (scene, use_action_tokens, _) = get_or_create_settings(conn, &campaign_id)?;
+
+        let (holder_id, holder_name, holder_campaign) = match (holder_type, holder_id) {
+            (SpotlightHolder::PlayerCharacter, Some(id)) => {
+                let character = get_player_character_by_id(conn, &id)?;
+                (Some(id), character.name, character.campaign_id)
+            }
+            (SpotlightHolder::Entity, Some(id)) => {
+                let entity = get_entity_by_id(conn, &id)?;
+                (Some(id), entity.name, entity.campaign_id)
+            }
+            (SpotlightHolder::Gm, _) => (None, "GM".to_string(), campaign_id.clone()),
+            (_, None) => {
+                return Err(AppError::Validation("holder_id is required for player characters and entities".to_string()));
+            }
+        };
+        if holder_campaign != campaign_id {
+            return Err(AppError::Validation(format!("{} belongs to a different campaign", holder_name)));
+        }
+
+        let previous = latest_change(conn, &campaign_id, scene)?;
+
+        let tx = conn.unchecked_transaction()?;
+        if use_action_tokens && holder_type == SpotlightHolder::PlayerCharacter {
+            let spent = tx.execute(
+                "UPDATE action_tokens SET tokens = tokens - 1 WHERE player_character_id = ?1 AND tokens > 0",
+                params![holder_id],
+            )?;
+            if spent == 0 {
+                return Err(AppError::InvalidOperation(format!("{} has no action tokens left", holder_name)));
+            }
+            tx.execute(
+                "UPDATE spotlight_state SET gm_tokens = gm_tokens + 1 WHERE campaign_id = ?1",
+                [&campaign_id],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO spotlight_changes (id, campaign_id, scene, holder_type, holder_id, holder_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                Uuid::new_v4().to_string(),
+                campaign_id,
+                scene,
+                holder_type.as_str(),
+                holder_id,
+                holder_name
+            ],
+        )?;
+
+        let gm_spotlight_ended = holder_type == SpotlightHolder::PlayerCharacter
+            && previous.is_some_and(|p| p.holder_type.is_gm_side());
+        let ticked = if gm_spotlight_ended {
+            Some(tick_conditions(&tx, &campaign_id)?)
+        } else {
+            None
+        };
+        tx.commit()?;
+
+        if let Some(ticked) = ticked {
+            let changed = ticked.ticks.iter().map(|t| &t.condition).chain(&ticked.expired);
+            emit_condition_changes(&app, conn, &campaign_id, changed)?;
+        }
+
+        let state = get_spotlight_state(conn, &campaign_id)?;
+        emit_spotlight_changed(&app, conn, &state)?;
+        Ok(state)
+    })
+}
+
+/// Start a new scene: spotlight counts start over, end-of-scene conditions
+/// clear, and with action tokens each active player character gets
+/// `tokens_per_character` (3 by default)
+#[tauri::command]
+pub fn start_spotlight_scene(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    use_action_tokens: Option<bool>,
+    tokens_per_character: Option<i32>,
+) -> AppResult<SpotlightState> {
+    let tokens = tokens_per_character.unwrap_or(DEFAULT_ACTION_TOKENS);
+    if tokens < 0 {
+        return Err(AppError::Validation("Action tokens cannot be negative".to_string()));
+    }
+
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        let (_, current_use_tokens, _) = get_or_create_settings(conn, &campaign_id)?;
+        let use_action_tokens = use_action_tokens.unwrap_or(current_use_tokens);
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE spotlight_state SET scene = scene + 1, use_action_tokens = ?1, gm_tokens = 0 WHERE campaign_id = ?2",
+            params![use_action_tokens, campaign_id],
+        )?;
+        tx.execute("DELETE FROM action_tokens WHERE campaign_id = ?1", [&campaign_id])?;
+        if use_action_tokens {
+            tx.execute(
+                "INSERT INTO action_tokens (player_character_id, campaign_id, tokens)
+                 SELECT id, campaign_id, ?2 FROM player_characters WHERE campaign_id = ?1 AND is_active = 1",
+                params![campaign_id, tokens],
+            )?;
+        }
+        let expired = expire_scene_conditions(&tx, &campaign_id)?;
+        tx.commit()?;
+
+        emit_condition_changes(&app, conn, &campaign_id, &expired)?;
+        let state = get_spotlight_state(conn, &campaign_id)?;
+        emit_spotlight_changed(&app, conn, &state)?;
+        Ok(state)
+    })
+}
+
+/// Give (positive) or take (negative) action tokens from a player character
+#[tauri::command]
+pub fn adjust_action_tokens(
+    db: State<Database>,
+    app: tauri::AppHandle,
+    player_character_id: String,
+    amount: i32,
+) -> AppResult<SpotlightState> {
+    db.with_conn(|conn| {
+        let character = get_player_character_by_id(conn, &player_character_id)?;
+
+        conn.execute(
+            "INSERT INTO action_tokens (player_character_id, campaign_id, tokens) VALUES (?1, ?2, MAX(0, ?3))
+             ON CONFLICT(player_character_id) DO UPDATE SET tokens = MAX(0, tokens + ?3)",
+            params![player_character_id, character.campaign_id, amount],
+        )?;
+
+        let state = get_spotlight_state(conn, &character.campaign_id)?;
+        emit_spotlight_changed(&app, conn, &state)?;
+        Ok(state)
+    })
+}
+
+/// Spotlight changes, newest first. `scene` defaults to the current one.
+#[tauri::command]
+pub fn get_spotlight_history(
+    db: State<Database>,
+    scene: Option<i32>,
+    limit: Option<u32>,
+) -> AppResult<Vec<SpotlightChange>> {
+    db.with_conn(|conn| {
+        let campaign_id = get_required_campaign_id(conn)?;
+        let scene = match scene {
+            Some(scene) => scene,
+            None => get_or_create_settings(conn, &campaign_id)?.0,
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM spotlight_changes WHERE campaign_id = ?1 AND scene = ?2 ORDER BY changed_at DESC, rowid DESC LIMIT ?3",
+            SELECT_COLUMNS
+        ))?;
+        let changes = stmt
+            .query_map(
+                params![campaign_id, scene, limit.unwrap_or(MAX_HISTORY).min(MAX_HISTORY)],
+                row_to_change,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(changes)
+    })
+}
@@ -34,6 +34,24 @@ CREATE TABLE IF NOT EXISTS entities (
     threshold_severe INTEGER NOT NULL,
     visible_to_players INTEGER NOT NULL DEFAULT 0,
     entity_type TEXT NOT NULL DEFAULT 'adversary',
+    difficulty INTEGER,  -- Target number for attacks against this entity
+    tier INTEGER NOT NULL DEFAULT 1,
+    role TEXT NOT NULL DEFAULT 'standard',  -- 'bruiser', 'horde', 'leader', 'minion', 'solo', ...
+    attack_modifier INTEGER NOT NULL DEFAULT 0,
+    attack_name TEXT,
+    attack_range TEXT,  -- 'melee', 'very_close', 'close', 'far' or 'very_far'
+    attack_damage TEXT,  -- Dice notation
+    motives_and_tactics TEXT,
+    experiences TEXT NOT NULL DEFAULT '[]',  -- JSON: array of { name, modifier }
+    features TEXT NOT NULL DEFAULT '[]',  -- JSON: array of { name, kind, description }
+    minion_threshold INTEGER,  -- X in "Minion (X)"
+    horde_damage TEXT,  -- Standard attack damage once a Horde is at half HP
+    group_id TEXT,  -- Minions sharing a group take spillover damage
+    physical_affinity TEXT NOT NULL DEFAULT 'normal',  -- 'normal', 'resistant', 'immune' or 'vulnerable'
+    magic_affinity TEXT NOT NULL DEFAULT 'normal',
+    armor_slots INTEGER NOT NULL DEFAULT 0,
+    attack_damage_type TEXT,  -- 'physical' or 'magic'
+    armor_marked INTEGER NOT NULL DEFAULT 0,
     FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
 );
 
@@ -47,8 +65,15 @@ CREATE TABLE IF NOT EXISTS countdown_trackers (
     visible_to_players INTEGER NOT NULL DEFAULT 0,
     hide_name_from_players INTEGER NOT NULL DEFAULT 0,
     tracker_type TEXT NOT NULL,
+    mode TEXT NOT NULL DEFAULT 'countdown',  -- 'countdown', 'count_up', 'progress' or 'looping'
+    cycle INTEGER NOT NULL DEFAULT 0,  -- Completed loops of a looping countdown
     auto_interval INTEGER NOT NULL DEFAULT 0,  -- Auto-countdown interval in seconds (0 = disabled)
     notify_on_complete INTEGER NOT NULL DEFAULT 1,  -- Show notification when auto-countdown hits 0
+    auto_state TEXT NOT NULL DEFAULT 'stopped',  -- 'stopped', 'running' or 'paused'
+    started_at INTEGER,  -- Unix ms when the auto-countdown was started
+    next_tick_at INTEGER,  -- Unix ms of the next scheduled tick while running
+    paused_remaining_ms INTEGER,  -- Time left until the next tick while paused
+    linked_to_rolls INTEGER NOT NULL DEFAULT 0,  -- Dynamic countdown advanced by duality roll outcomes
     FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
 );
 
@@ -57,6 +82,7 @@ CREATE TABLE IF NOT EXISTS tick_labels (
     tracker_id TEXT NOT NULL,
     tick INTEGER NOT NULL,
     label TEXT NOT NULL,
+    revealed INTEGER NOT NULL DEFAULT 0,  -- Revealed to players by a completion action
     PRIMARY KEY (tracker_id, tick),
     FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE
 );
@@ -122,11 +148,31 @@ CREATE TABLE IF NOT EXISTS player_characters (
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now')),
 
+    -- Counted in the party for encounter budgets
+    is_active INTEGER NOT NULL DEFAULT 1,
+
     FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
 );
 
 CREATE INDEX IF NOT EXISTS idx_player_characters_campaign ON player_characters(campaign_id);
 
+-- Seeds for reproducible dice rolls: 'seeded' (known seed, for replays) or
+-- 'committed' (seed hash published up front, seed revealed when the session ends)
+CREATE TABLE IF NOT EXISTS dice_rng_sessions (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    mode TEXT NOT NULL DEFAULT 'committed',
+    seed TEXT NOT NULL,
+    seed_hash TEXT NOT NULL,  -- Hex SHA-256 of seed
+    client_seed TEXT NOT NULL DEFAULT '',
+    next_nonce INTEGER NOT NULL DEFAULT 0,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    ended_at TEXT,  -- NULL while active; committed seeds are revealed once set
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dice_rng_sessions_campaign ON dice_rng_sessions(campaign_id);
+
 -- Dice rolls history
 CREATE TABLE IF NOT EXISTS dice_rolls (
     id TEXT PRIMARY KEY,
@@ -139,13 +185,240 @@ CREATE TABLE IF NOT EXISTS dice_rolls (
     is_fumble INTEGER NOT NULL DEFAULT 0,
     shared_with_players INTEGER NOT NULL DEFAULT 0,
     rolled_at TEXT NOT NULL DEFAULT (datetime('now')),
+    roll_type TEXT NOT NULL DEFAULT 'standard',  -- 'standard' or 'duality'
+    outcome TEXT,  -- Duality outcome, e.g. 'success_with_hope'
+    difficulty INTEGER,
+    player_character_id TEXT REFERENCES player_characters(id) ON DELETE SET NULL,
+    is_secret INTEGER NOT NULL DEFAULT 0,  -- GM-only roll that can be revealed later
+    revealed_at TEXT,
+    rng_session_id TEXT REFERENCES dice_rng_sessions(id) ON DELETE SET NULL,
+    rng_nonce INTEGER,  -- Roll number within the seed session
+    roll_options TEXT,  -- JSON RollOptions, needed to replay the roll
     FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
 );
 
 CREATE INDEX IF NOT EXISTS idx_dice_rolls_campaign ON dice_rolls(campaign_id);
 CREATE INDEX IF NOT EXISTS idx_dice_rolls_date ON dice_rolls(rolled_at);
 
+-- Saved roll macros, campaign-wide or bound to one player character
+CREATE TABLE IF NOT EXISTS roll_macros (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    player_character_id TEXT,
+    name TEXT NOT NULL,
+    notation TEXT NOT NULL,  -- May reference @agility, @proficiency, @exp:Stealth, ...
+    roll_type TEXT NOT NULL DEFAULT 'standard',  -- 'standard' or 'duality' (notation is the modifier)
+    difficulty INTEGER,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+    FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_roll_macros_campaign ON roll_macros(campaign_id);
+
+-- Resolved attacks: the attack roll, the damage roll on a hit, and the damage applied
+CREATE TABLE IF NOT EXISTS attack_results (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    attacker_type TEXT NOT NULL,  -- 'player_character' or 'entity'
+    attacker_id TEXT NOT NULL,
+    target_entity_id TEXT NOT NULL,
+    attack_roll_id TEXT REFERENCES dice_rolls(id) ON DELETE SET NULL,
+    damage_roll_id TEXT REFERENCES dice_rolls(id) ON DELETE SET NULL,  -- NULL on a miss
+    difficulty INTEGER NOT NULL,
+    hit INTEGER NOT NULL DEFAULT 0,
+    critical INTEGER NOT NULL DEFAULT 0,
+    damage_total INTEGER NOT NULL DEFAULT 0,
+    hp_marked INTEGER NOT NULL DEFAULT 0,
+    threshold_hit TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+    FOREIGN KEY (target_entity_id) REFERENCES entities(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_attack_results_campaign ON attack_results(campaign_id);
+
+-- Reusable adversary and NPC templates shared by all campaigns
+CREATE TABLE IF NOT EXISTS adversary_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    entity_type TEXT NOT NULL DEFAULT 'adversary',
+    hp_max INTEGER NOT NULL,
+    stress_max INTEGER NOT NULL DEFAULT 0,
+    threshold_minor INTEGER NOT NULL,
+    threshold_major INTEGER NOT NULL,
+    threshold_severe INTEGER NOT NULL,
+    difficulty INTEGER,  -- Stat block columns match entities
+    tier INTEGER NOT NULL DEFAULT 1,
+    role TEXT NOT NULL DEFAULT 'standard',
+    attack_modifier INTEGER NOT NULL DEFAULT 0,
+    attack_name TEXT,
+    attack_range TEXT,
+    attack_damage TEXT,
+    motives_and_tactics TEXT,
+    experiences TEXT NOT NULL DEFAULT '[]',
+    features TEXT NOT NULL DEFAULT '[]',
+    minion_threshold INTEGER,
+    horde_damage TEXT,
+    physical_affinity TEXT NOT NULL DEFAULT 'normal',
+    magic_affinity TEXT NOT NULL DEFAULT 'normal',
+    armor_slots INTEGER NOT NULL DEFAULT 0,
+    attack_damage_type TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_adversary_templates_name ON adversary_templates(name);
+
+-- Conditions on an entity or player character (exactly one of the two is set)
+CREATE TABLE IF NOT EXISTS conditions (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    entity_id TEXT,
+    player_character_id TEXT,
+    kind TEXT NOT NULL,  -- 'vulnerable', 'restrained', 'hidden' or 'custom'
+    name TEXT NOT NULL,
+    description TEXT,
+    visible_to_players INTEGER NOT NULL DEFAULT 1,
+    duration_type TEXT NOT NULL DEFAULT 'indefinite',  -- 'indefinite', 'end_of_scene', 'spotlights' or 'countdown'
+    spotlights_remaining INTEGER,
+    countdown_id TEXT,
+    tick_damage INTEGER NOT NULL DEFAULT 0,  -- HP marked each GM spotlight
+    tick_stress INTEGER NOT NULL DEFAULT 0,  -- Stress marked each GM spotlight
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+    FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE,
+    FOREIGN KEY (countdown_id) REFERENCES countdown_trackers(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_conditions_campaign ON conditions(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_conditions_entity ON conditions(entity_id);
+CREATE INDEX IF NOT EXISTS idx_conditions_player_character ON conditions(player_character_id);
+
+-- Encounters staged ahead of time, budgeted in Battle Points
+CREATE TABLE IF NOT EXISTS encounters (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    notes TEXT,
+    intensity TEXT NOT NULL DEFAULT 'standard',  -- 'easier', 'standard' or 'harder'
+    damage_boost INTEGER NOT NULL DEFAULT 0,  -- +1d4 damage for all adversaries
+    status TEXT NOT NULL DEFAULT 'staged',  -- 'staged', 'active' or 'completed'
+    activated_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+-- Entities in an encounter (each entity is in at most one)
+CREATE TABLE IF NOT EXISTS encounter_entities (
+    entity_id TEXT PRIMARY KEY,
+    encounter_id TEXT NOT NULL,
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+    FOREIGN KEY (encounter_id) REFERENCES encounters(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_encounters_campaign ON encounters(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_encounter_entities_encounter ON encounter_entities(encounter_id);
+
+-- Each campaign's current spotlight scene
+CREATE TABLE IF NOT EXISTS spotlight_state (
+    campaign_id TEXT PRIMARY KEY,
+    scene INTEGER NOT NULL DEFAULT 1,
+    use_action_tokens INTEGER NOT NULL DEFAULT 0,
+    gm_tokens INTEGER NOT NULL DEFAULT 0,  -- Action tokens spent by players this scene
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+-- Every time the spotlight moves; the latest in a scene is the current holder
+CREATE TABLE IF NOT EXISTS spotlight_changes (
+    id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    scene INTEGER NOT NULL,
+    holder_type TEXT NOT NULL,  -- 'player_character', 'entity' or 'gm'
+    holder_id TEXT,
+    holder_name TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+-- Action tokens left for each player character this scene
+CREATE TABLE IF NOT EXISTS action_tokens (
+    player_character_id TEXT PRIMARY KEY,
+    campaign_id TEXT NOT NULL,
+    tokens INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (player_character_id) REFERENCES player_characters(id) ON DELETE CASCADE,
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_spotlight_changes_scene ON spotlight_changes(campaign_id, scene);
+
+-- History of HP, Stress and Armor changes to entities, for the timeline and undo
+CREATE TABLE IF NOT EXISTS entity_events (
+    id TEXT PRIMARY KEY,
+    entity_id TEXT NOT NULL,
+    campaign_id TEXT NOT NULL,
+    kind TEXT NOT NULL,  -- 'damage', 'hp_change', 'hp_set' or 'stress'
+    source TEXT NOT NULL,  -- 'manual', 'attack', 'bulk', 'minion_spillover' or 'condition'
+    raw_amount INTEGER NOT NULL,
+    damage_type TEXT,
+    threshold_hit TEXT,
+    hp_before INTEGER NOT NULL,
+    hp_after INTEGER NOT NULL,
+    stress_before INTEGER NOT NULL,
+    stress_after INTEGER NOT NULL,
+    armor_before INTEGER NOT NULL DEFAULT 0,
+    armor_after INTEGER NOT NULL DEFAULT 0,
+    undone INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    parent_event_id TEXT,  -- The hit a Minion spillover event came from
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_events_entity ON entity_events(entity_id, created_at);
+
+-- How far a dynamic countdown advances for each duality roll outcome
+CREATE TABLE IF NOT EXISTS tracker_advancement (
+    tracker_id TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    PRIMARY KEY (tracker_id, outcome),
+    FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE
+);
+
+-- Every time a roll advanced a dynamic countdown, and why
+CREATE TABLE IF NOT EXISTS tracker_advances (
+    id TEXT PRIMARY KEY,
+    tracker_id TEXT NOT NULL,
+    campaign_id TEXT NOT NULL,
+    dice_roll_id TEXT,
+    outcome TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    value_before INTEGER NOT NULL,
+    value_after INTEGER NOT NULL,
+    completion TEXT,  -- How the advance completed the tracker, if it did
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE,
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE,
+    FOREIGN KEY (dice_roll_id) REFERENCES dice_rolls(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tracker_advances_tracker ON tracker_advances(tracker_id, created_at);
+
+-- Actions run, in order, when a tracker completes (JSON-encoded CompletionAction)
+CREATE TABLE IF NOT EXISTS tracker_actions (
+    tracker_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    PRIMARY KEY (tracker_id, position),
+    FOREIGN KEY (tracker_id) REFERENCES countdown_trackers(id) ON DELETE CASCADE
+);
+
 -- Index for faster campaign-scoped queries
 CREATE INDEX IF NOT EXISTS idx_entities_campaign ON entities(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_entities_group ON entities(group_id);
 CREATE INDEX IF NOT EXISTS idx_trackers_campaign ON countdown_trackers(campaign_id);
 CREATE INDEX IF NOT EXISTS idx_app_state_campaign ON app_state(campaign_id);
@@ -47,6 +47,13 @@ class CountdownDisplay extends CampaignAwareMixin(ExtendedHtmlElement) {
       item.setAttribute('name', tracker.name);
       item.setAttribute('current', tracker.current);
       item.setAttribute('max', tracker.max);
+      item.setAttribute('mode', tracker.mode);
+      const revealedLabels = (tracker.revealed_ticks || [])
+        .map(tick => tracker.tick_labels?.[tick])
+        .filter(Boolean);
+      if (revealedLabels.length > 0) {
+        item.setAttribute('label', revealedLabels.join(' · '));
+      }
       if (tracker.hide_name_from_players) {
         item.setAttribute('hide-name', '');
       }
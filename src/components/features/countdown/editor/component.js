@@ -1,12 +1,14 @@
 import ExtendedHtmlElement from '../../../base/extended-html-element.js';
 import { CampaignAwareMixin } from '../../../../helpers/campaign-aware-mixin.js';
 import { safeInvoke } from '../../../../helpers/tauri.js';
+import createWindow from '../../../../helpers/create-window.js';
 
 class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
   static moduleUrl = import.meta.url;
   #modal;
   #nameField;
   #maxField;
+  #modeSelect;
   #autoIntervalField;
   #notifyToggle;
   #visibleToggle;
@@ -24,6 +26,7 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
     this.#modal = this.$('modal-dialog');
     this.#nameField = this.$('form-field[name="name"]');
     this.#maxField = this.$('form-field[name="max"]');
+    this.#modeSelect = this.$('select[name="mode"]');
     this.#autoIntervalField = this.$('form-field[name="autoInterval"]');
     this.#notifyToggle = this.$('toggle-switch[name="notifyOnComplete"]');
     this.#visibleToggle = this.$('visibility-toggle[name="visibleToPlayers"]');
@@ -46,6 +49,11 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
         'trackers-updated': (payload) => {
           this.trackers = payload.trackers;
           this.renderTrackers();
+        },
+        'countdown-completed': (payload) => {
+          if (payload.notify) {
+            this.showCompletionAlert(payload.tracker, payload.tick_label);
+          }
         }
       }
     });
@@ -73,6 +81,7 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
     this.addEventListener('visibility-change', this.handleVisibilityChange.bind(this));
     this.addEventListener('name-visibility-change', this.handleNameVisibilityChange.bind(this));
     this.addEventListener('delete', this.handleDelete.bind(this));
+    this.addEventListener('auto-countdown-toggle', this.handleAutoCountdownToggle.bind(this));
   }
 
   updateNotifyToggleVisibility() {
@@ -158,6 +167,7 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
 
     const name = this.#nameField.value.trim();
     const max = parseInt(this.#maxField.value);
+    const mode = this.#modeSelect.value;
     const autoInterval = parseInt(this.#autoIntervalField.value) || 0;
     const notifyOnComplete = autoInterval > 0 ? this.#notifyToggle.checked : false;
     const visibleToPlayers = this.#visibleToggle.checked;
@@ -181,6 +191,7 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
       trackerType,
       autoInterval,
       notifyOnComplete,
+      mode,
     }, { errorMessage: 'Failed to create tracker' });
 
     if (!tracker) return;
@@ -215,6 +226,7 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
     // Reset form
     this.#nameField.value = '';
     this.#maxField.value = '10';
+    this.#modeSelect.value = 'countdown';
     this.#autoIntervalField.value = '0';
     this.#notifyToggle.checked = true;
     this.#notifyToggle.hidden = true;
@@ -256,6 +268,41 @@ class CountdownEditor extends CampaignAwareMixin(ExtendedHtmlElement) {
     });
   }
 
+  async handleAutoCountdownToggle(event) {
+    const { id, state } = event.detail;
+    const command = {
+      running: 'pause_auto_countdown',
+      paused: 'resume_auto_countdown',
+    }[state] || 'start_auto_countdown';
+
+    await safeInvoke(command, { id }, {
+      errorMessage: 'Failed to update auto-countdown'
+    });
+  }
+
+  async showCompletionAlert(tracker, tickLabel) {
+    const windowLabel = `countdown-alert-${tracker.id}`;
+    const encodedName = encodeURIComponent(tracker.name);
+
+    let url = `/pages/countdown-alert/index.html?id=${tracker.id}&name=${encodedName}`;
+    if (tickLabel) {
+      url += `&tickLabel=${encodeURIComponent(tickLabel)}`;
+    }
+
+    await createWindow(windowLabel, {
+      url,
+      title: 'Countdown Complete',
+      width: 450,
+      height: 320,
+      resizable: false,
+      alwaysOnTop: true,
+      center: true,
+    }, {
+      focusIfExists: true,
+      cascade: false,
+    });
+  }
+
   renderTrackers() {
     if (this.trackers.length === 0) {
       this.#trackersList.innerHTML = '<empty-state message="No trackers yet"></empty-state>';
@@ -1,6 +1,5 @@
 import ExtendedHtmlElement from '../../../base/extended-html-element.js';
 import '../../../layout/flex-row/component.js';
-import createWindow from '../../../../helpers/create-window.js';
 
 /**
  * A component for rendering a single countdown tracker item.
@@ -14,12 +13,11 @@ import createWindow from '../../../../helpers/create-window.js';
  *   - visibility-change: { id, visible } - When visibility toggle changes
  *   - name-visibility-change: { id, hidden } - When hide name toggle changes
  *   - delete: { id, name } - When tracker is deleted (after fade-out)
+ *   - auto-countdown-toggle: { id, state } - When play/pause is clicked (state is the tracker's auto_state)
  */
 class CountdownItem extends ExtendedHtmlElement {
   static moduleUrl = import.meta.url;
   #tracker = null;
-  #timerInterval = null;
-  #isRunning = false;
   stylesPath = './styles.css';
   templatePath = './template.html';
 
@@ -71,7 +69,7 @@ class CountdownItem extends ExtendedHtmlElement {
     // Play/pause button click handler
     this.shadowRoot.addEventListener('action-click', e => {
       if (e.target.closest('.play-pause-btn')) {
-        this.toggleTimer();
+        this.emit('auto-countdown-toggle', { id: this.#tracker.id, state: this.#tracker.auto_state });
       }
     });
 
@@ -80,10 +78,6 @@ class CountdownItem extends ExtendedHtmlElement {
     }
   }
 
-  cleanup() {
-    this.stopTimer();
-  }
-
   /**
    * Initial binding of tracker data to template elements
    */
@@ -113,10 +107,14 @@ class CountdownItem extends ExtendedHtmlElement {
     deleteTrigger.setAttribute('item-name', tracker.name);
     deleteTrigger.setAttribute('item-id', tracker.id);
 
-    // Counter control
+    // Counter control (count-up trackers can pass their target)
     const counter = this.$('counter-control');
     counter.setAttribute('min', '0');
-    counter.setAttribute('max', tracker.max);
+    if (tracker.mode === 'count_up') {
+      counter.removeAttribute('max');
+    } else {
+      counter.setAttribute('max', tracker.max);
+    }
     counter.setAttribute('show-max', tracker.max);
 
     // Update dynamic values
@@ -149,6 +147,18 @@ class CountdownItem extends ExtendedHtmlElement {
       hideNameToggle.checked = tracker.hide_name_from_players;
     }
 
+    // Mode badge (plain countdowns need none)
+    const modeBadge = this.$('.mode-badge');
+    if (modeBadge) {
+      const modeLabels = {
+        count_up: '↑ Count up',
+        progress: '◔ Progress',
+        looping: `↻ Cycle ${tracker.cycle + 1}`,
+      };
+      modeBadge.hidden = !modeLabels[tracker.mode];
+      modeBadge.textContent = modeLabels[tracker.mode] || '';
+    }
+
     // Auto interval badge and play/pause button
     const hasAutoInterval = tracker.auto_interval > 0;
     const intervalBadge = this.$('.auto-interval-badge');
@@ -164,6 +174,7 @@ class CountdownItem extends ExtendedHtmlElement {
 
     if (playPauseBtn) {
       playPauseBtn.hidden = !hasAutoInterval;
+      this.updatePlayPauseButton();
     }
 
     // Current label (for complex trackers)
@@ -180,75 +191,12 @@ class CountdownItem extends ExtendedHtmlElement {
     }
   }
 
-  toggleTimer() {
-    if (this.#isRunning) {
-      this.stopTimer();
-    } else {
-      this.startTimer();
-    }
-    this.updatePlayPauseButton();
-  }
-
-  startTimer() {
-    if (this.#isRunning || !this.#tracker?.auto_interval) return;
-
-    this.#isRunning = true;
-    this.#timerInterval = setInterval(() => {
-      if (this.#tracker.current > 0) {
-        this.emit('value-change', { id: this.#tracker.id, delta: -1 });
-
-        // Check if we just hit 0 (current was 1, now will be 0)
-        if (this.#tracker.current === 1 && this.#tracker.notify_on_complete) {
-          this.showCompletionAlert();
-        }
-      } else {
-        this.stopTimer();
-        this.updatePlayPauseButton();
-      }
-    }, this.#tracker.auto_interval * 1000);
-  }
-
-  async showCompletionAlert() {
-    const tracker = this.#tracker;
-    const windowLabel = `countdown-alert-${tracker.id}`;
-    const encodedName = encodeURIComponent(tracker.name);
-
-    // Get the tick label for tick 0 if it exists (for complex trackers)
-    const tickLabel = tracker.tick_labels?.[0] || '';
-    const encodedTickLabel = encodeURIComponent(tickLabel);
-
-    let url = `/pages/countdown-alert/index.html?id=${tracker.id}&name=${encodedName}`;
-    if (tickLabel) {
-      url += `&tickLabel=${encodedTickLabel}`;
-    }
-
-    await createWindow(windowLabel, {
-      url,
-      title: 'Countdown Complete',
-      width: 450,
-      height: 320,
-      resizable: false,
-      alwaysOnTop: true,
-      center: true,
-    }, {
-      focusIfExists: true,
-      cascade: false,
-    });
-  }
-
-  stopTimer() {
-    if (this.#timerInterval) {
-      clearInterval(this.#timerInterval);
-      this.#timerInterval = null;
-    }
-    this.#isRunning = false;
-  }
-
   updatePlayPauseButton() {
     const btn = this.$('.play-pause-btn');
     if (btn) {
-      btn.textContent = this.#isRunning ? '⏸' : '▶';
-      btn.setAttribute('variant', this.#isRunning ? 'warning' : 'success');
+      const isRunning = this.#tracker?.auto_state === 'running';
+      btn.textContent = isRunning ? '⏸' : '▶';
+      btn.setAttribute('variant', isRunning ? 'warning' : 'success');
     }
   }
 }
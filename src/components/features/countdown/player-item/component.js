@@ -18,15 +18,18 @@ import { getUrgencyByPercentage } from '../../../../helpers/urgency-utils.js';
  *   - name: Tracker name to display
  *   - current: Current countdown value
  *   - max: Maximum countdown value (for percentage-based urgency)
+ *   - mode: Tracker mode; count_up and progress trackers grow toward max
+ *   - label: Tick label revealed to players, if any
  *   - hide-name: If present, shows "???" instead of name
  */
 class CountdownPlayerItem extends ExtendedHtmlElement {
   static moduleUrl = import.meta.url;
-  static observedAttributes = ['name', 'current', 'max', 'hide-name'];
+  static observedAttributes = ['name', 'current', 'max', 'mode', 'label', 'hide-name'];
 
   #pulseContainer;
   #nameEl;
   #counterEl;
+  #labelEl;
   stylesPath = './styles.css';
   templatePath = './template.html';
 
@@ -34,6 +37,7 @@ class CountdownPlayerItem extends ExtendedHtmlElement {
     this.#pulseContainer = this.$('pulse-container');
     this.#nameEl = this.$('.countdown-name');
     this.#counterEl = this.$('counter-control');
+    this.#labelEl = this.$('.revealed-label');
 
     this.updateDisplay();
   }
@@ -48,14 +52,20 @@ class CountdownPlayerItem extends ExtendedHtmlElement {
     const name = this.getStringAttr('name', '');
     const current = this.getIntAttr('current', 0);
     const max = this.getIntAttr('max', 10);
+    const mode = this.getStringAttr('mode', 'countdown');
     const hideName = this.getBoolAttr('hide-name');
 
     const displayName = hideName ? '???' : name;
-    const urgency = getUrgencyByPercentage(current, max);
+    const countsUp = mode === 'count_up' || mode === 'progress';
+    const urgency = getUrgencyByPercentage(countsUp ? max - current : current, max);
 
     this.#nameEl.textContent = displayName;
     this.#counterEl.setAttribute('value', current);
     this.#pulseContainer.setAttribute('urgency', urgency);
+
+    const label = this.getStringAttr('label', '');
+    this.#labelEl.hidden = !label;
+    this.#labelEl.textContent = label;
   }
 }
 
@@ -89,7 +89,7 @@ class DiceHistoryItem extends ExtendedHtmlElement {
         if (i > 0) {
           const plus = document.createElement('span');
           plus.className = 'roll-operator';
-          plus.textContent = '+';
+          plus.textContent = d.kind === 'disadvantage' ? '−' : '+';
           diceContainer.appendChild(plus);
         }
         const die = document.createElement('die-shape');
@@ -98,6 +98,13 @@ class DiceHistoryItem extends ExtendedHtmlElement {
         if (d.colorIndex !== undefined) {
           die.setAttribute('color', d.colorIndex);
         }
+        if (d.kind && d.kind !== 'standard') {
+          die.setAttribute('kind', d.kind);
+          die.title = d.label || d.kind;
+        }
+        if (d.dropped) {
+          die.setAttribute('dropped', '');
+        }
         die.style.setProperty('--die-size', '28px');
         diceContainer.appendChild(die);
       });
@@ -78,7 +78,7 @@ class DiceResultDisplay extends ExtendedHtmlElement {
         if (i > 0) {
           const plus = document.createElement('span');
           plus.className = 'roll-operator';
-          plus.textContent = '+';
+          plus.textContent = d.kind === 'disadvantage' ? '−' : '+';
           this.#diceContainer.appendChild(plus);
         }
 
@@ -88,6 +88,13 @@ class DiceResultDisplay extends ExtendedHtmlElement {
         if (d.colorIndex !== undefined) {
           die.setAttribute('color', d.colorIndex);
         }
+        if (d.kind && d.kind !== 'standard') {
+          die.setAttribute('kind', d.kind);
+          die.title = d.label || d.kind;
+        }
+        if (d.dropped) {
+          die.setAttribute('dropped', '');
+        }
         die.style.setProperty('--die-size', '48px');
         this.#diceContainer.appendChild(die);
       });
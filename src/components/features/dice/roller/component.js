@@ -1,6 +1,5 @@
 import ExtendedHtmlElement from '../../../base/extended-html-element.js';
 import { formatRelativeDate } from '../../../../helpers/date-utils.js';
-import { safeJsonParse } from '../../../../helpers/string-utils.js';
 import '../shape/component.js';
 import '../history-item/component.js';
 import '../../../ui/input-group/component.js';
@@ -13,7 +12,7 @@ import { emit, safeInvoke } from '../../../../helpers/tauri.js';
 
 /**
  * Dice bag component with history and custom roll notation support.
- * Supports standard dice notation: NdX+M (e.g., 2d6+3, 1d20, 3d8-2)
+ * Typed notation is parsed and rolled by the backend `roll_dice` command.
  * Persists rolls to the database organized by date.
  */
 class DiceRoller extends ExtendedHtmlElement {
@@ -153,42 +152,35 @@ class DiceRoller extends ExtendedHtmlElement {
     });
 
     // When share toggle is enabled, share the most recent roll from today
-    // When disabled, hide the result from the player view
+    // When disabled, hide it from the player view again
     this.#shareCheckbox.addEventListener('toggle-change', (e) => {
-      if (e.detail.checked) {
-        this.shareLatestRoll();
-      } else {
-        emit('dice-roll-hidden');
-      }
+      this.setLatestRollShared(e.detail.checked);
     });
   }
 
   /**
-   * Share the most recent roll from today with players (if any exists)
+   * Share or hide the most recent roll from today (if any exists).
+   * The backend emits 'dice-roll-shared' / 'dice-roll-hidden' to the player view.
+   * @param {boolean} shared
    */
-  shareLatestRoll() {
-    if (this.#rollsByDate.length === 0) return;
-
-    // Check if the first group is today
+  async setLatestRollShared(shared) {
     const firstGroup = this.#rollsByDate[0];
-    const today = new Date().toISOString().split('T')[0];
-
-    if (firstGroup.date === today && firstGroup.rolls.length > 0) {
-      const latestRoll = firstGroup.rolls[0];
-
-      // Parse dice_data if it's a string
-      const diceResults = safeJsonParse(latestRoll.dice_data, []);
-
-      // Emit in the format expected by the player view
-      emit('dice-roll-shared', {
-        notation: latestRoll.notation,
-        diceResults,
-        total: latestRoll.total,
-        modifier: latestRoll.modifier || 0,
-        isCrit: latestRoll.is_crit,
-        isFumble: latestRoll.is_fumble
-      });
+    const now = new Date();
+    const today = [
+      now.getFullYear(),
+      String(now.getMonth() + 1).padStart(2, '0'),
+      String(now.getDate()).padStart(2, '0')
+    ].join('-');
+
+    if (!firstGroup || firstGroup.date !== today || firstGroup.rolls.length === 0) {
+      if (!shared) emit('dice-roll-hidden');
+      return;
     }
+
+    await safeInvoke('set_dice_roll_shared', {
+      id: firstGroup.rolls[0].id,
+      shared
+    }, { errorMessage: 'Failed to update roll sharing' });
   }
 
   async loadCampaignAndRolls() {
@@ -206,12 +198,15 @@ class DiceRoller extends ExtendedHtmlElement {
       return;
     }
 
-    const rollsByDate = await safeInvoke('get_dice_rolls_by_date', {
+    // Group by local day rather than the UTC date stored in the database
+    const page = await safeInvoke('get_dice_rolls_by_date', {
       campaignId: this.#campaignId,
-      limit: 100
+      limit: 100,
+      utcOffsetMinutes: -new Date().getTimezoneOffset()
     }, { errorMessage: 'Failed to load rolls' });
 
-    if (!rollsByDate) return;
+    if (!page) return;
+    const rollsByDate = page.groups;
 
     // Preserve expanded state from existing groups, or default to first expanded
     const existingState = new Map(
@@ -235,22 +230,6 @@ class DiceRoller extends ExtendedHtmlElement {
     this.renderHistory();
   }
 
-  async saveRoll(rollResult) {
-    if (!this.#campaignId) return;
-
-    await safeInvoke('save_dice_roll', {
-      campaignId: this.#campaignId,
-      notation: rollResult.notation,
-      diceData: JSON.stringify(rollResult.diceResults || []),
-      modifier: rollResult.modifier || 0,
-      total: rollResult.total,
-      isCrit: rollResult.isCrit || false,
-      isFumble: rollResult.isFumble || false,
-      sharedWithPlayers: this.#shareCheckbox.checked
-    }, { errorMessage: 'Failed to save roll' });
-    // The dice-roll-saved event will trigger a reload
-  }
-
   updateGhostPosition(x, y) {
     if (this.#dragGhost) {
       this.#dragGhost.style.left = `${x}px`;
@@ -310,56 +289,18 @@ class DiceRoller extends ExtendedHtmlElement {
   rollDroppedDice() {
     if (this.#droppedDice.length === 0) return;
 
-    // Roll each die individually and track results with color
-    const diceResults = this.#droppedDice.map(die => ({
-      sides: die.sides,
-      colorIndex: die.colorIndex,
-      result: this.rollDie(die.sides)
-    }));
-
-    // Group for notation display (e.g., "2d6 + 1d4")
-    const groupedNotation = [];
+    // Group for notation (e.g., "2d6 + 1d4"), largest dice first
     const diceGroups = {};
     this.#droppedDice.forEach(die => {
       diceGroups[die.sides] = (diceGroups[die.sides] || 0) + 1;
     });
+    const notation = Object.keys(diceGroups)
+      .sort((a, b) => parseInt(b) - parseInt(a))
+      .map(sides => `${diceGroups[sides]}d${sides}`)
+      .join(' + ');
 
-    // Sort by sides for consistent display
-    const sortedSides = Object.keys(diceGroups).sort((a, b) => parseInt(b) - parseInt(a));
-    sortedSides.forEach(sides => {
-      const count = diceGroups[sides];
-      groupedNotation.push(`${count}d${sides}`);
-    });
-
-    const notation = groupedNotation.join(' + ');
-    const total = diceResults.reduce((sum, d) => sum + d.result, 0);
-
-    // Build breakdown string showing each roll
-    const breakdownParts = diceResults.map(d => d.result);
-    const breakdown = breakdownParts.join(' + ');
-
-    // Check for crits/fumbles (only if single d20)
-    const isCrit = this.#droppedDice.length === 1 &&
-                   this.#droppedDice[0].sides === 20 &&
-                   diceResults[0].result === 20;
-    const isFumble = this.#droppedDice.length === 1 &&
-                     this.#droppedDice[0].sides === 20 &&
-                     diceResults[0].result === 1;
-
-    const rollResult = {
-      notation,
-      isMultiDice: true,
-      diceResults,
-      breakdown,
-      total,
-      isCrit,
-      isFumble,
-      modifier: 0
-    };
-
-    // Save to database (will trigger reload via event)
-    this.saveRoll(rollResult);
-    this.maybeShareRoll(rollResult);
+    // Rolled and saved on the backend (will trigger reload via event)
+    this.roll(notation);
 
     // Clear drop zone and reset color index
     this.#droppedDice = [];
@@ -368,90 +309,20 @@ class DiceRoller extends ExtendedHtmlElement {
   }
 
   /**
-   * Share roll with players if sharing is enabled
-   */
-  maybeShareRoll(rollResult) {
-    if (this.#shareCheckbox.checked) {
-      emit('dice-roll-shared', rollResult);
-    }
-  }
-
-  /**
-   * Parse and roll dice notation
+   * Roll dice notation on the backend, which parses, rolls and persists it.
+   * Supports groups, keep/drop (4d6kh3), exploding (3d6!), rerolls (1d20ro1)
+   * and arithmetic, e.g. "2d6+1d4+3".
    * @param {string} notation - Dice notation (e.g., "2d6+3", "1d20", "3d8-2")
    */
-  roll(notation) {
-    const parsed = this.parseNotation(notation);
-    if (!parsed) {
-      console.error('Invalid dice notation:', notation);
-      return;
-    }
-
-    const { count, sides, modifier } = parsed;
-    const individualRolls = [];
-
-    for (let i = 0; i < count; i++) {
-      individualRolls.push(this.rollDie(sides));
-    }
-
-    const diceTotal = individualRolls.reduce((sum, r) => sum + r, 0);
-    const total = diceTotal + modifier;
-
-    // Check for crits/fumbles on d20
-    const isCrit = sides === 20 && count === 1 && individualRolls[0] === 20;
-    const isFumble = sides === 20 && count === 1 && individualRolls[0] === 1;
-
-    // Create diceResults for consistent format
-    const diceResults = individualRolls.map(result => ({
-      sides,
-      result
-    }));
+  async roll(notation) {
+    if (!this.#campaignId) return;
 
-    const rollResult = {
+    // The backend emits 'dice-roll-shared' itself when sharing is enabled
+    await safeInvoke('roll_dice', {
+      campaignId: this.#campaignId,
       notation,
-      count,
-      sides,
-      modifier,
-      individualRolls,
-      diceResults,
-      total,
-      isCrit,
-      isFumble
-    };
-
-    // Save to database (will trigger reload via event)
-    this.saveRoll(rollResult);
-    this.maybeShareRoll(rollResult);
-  }
-
-  /**
-   * Parse dice notation string
-   * @param {string} notation - e.g., "2d6+3", "1d20", "d8", "3d6-1"
-   * @returns {{ count: number, sides: number, modifier: number } | null}
-   */
-  parseNotation(notation) {
-    // Match patterns like: 2d6, d20, 3d8+2, 1d6-1
-    const match = notation.toLowerCase().match(/^(\d*)d(\d+)([+-]\d+)?$/);
-    if (!match) return null;
-
-    const count = match[1] ? parseInt(match[1], 10) : 1;
-    const sides = parseInt(match[2], 10);
-    const modifier = match[3] ? parseInt(match[3], 10) : 0;
-
-    if (count < 1 || count > 100 || sides < 2 || sides > 1000) {
-      return null;
-    }
-
-    return { count, sides, modifier };
-  }
-
-  /**
-   * Roll a single die
-   * @param {number} sides - Number of sides
-   * @returns {number}
-   */
-  rollDie(sides) {
-    return Math.floor(Math.random() * sides) + 1;
+      sharedWithPlayers: this.#shareCheckbox.checked
+    }, { errorMessage: 'Invalid dice notation' });
   }
 
   renderHistory() {
@@ -11,6 +11,8 @@ import ExtendedHtmlElement from '../../../base/extended-html-element.js';
  * Attributes:
  *   - sides: Number of sides (4, 6, 8, 10, 12, 20, 100)
  *   - result: Optional custom label (e.g., the roll result) - overrides default label
+ *   - kind: Optional die role from a backend roll (hope, fear, advantage, disadvantage, bonus)
+ *   - dropped: Present when the die was excluded by a keep/drop modifier
  */
 class DieShape extends ExtendedHtmlElement {
   static moduleUrl = import.meta.url;
@@ -20,8 +20,9 @@ export default class EntityPlayerDisplay extends CampaignAwareMixin(ExtendedHtml
     await this.setupCampaignAwareness({
       loadData: () => this.loadEntities(),
       events: {
-        'entities-updated': (payload) => {
-          const newEntities = payload.entities.filter(e => e.visible_to_players);
+        // Filtered by the backend: hidden entities and conditions never reach this window
+        'player-entities-updated': (payload) => {
+          const newEntities = payload.entities;
           this.detectHpChanges(newEntities);
           this.entities = newEntities;
           this.render();